@@ -1,17 +1,27 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![feature(min_specialization)]
 
-#[openbrush::contract]
+#[openbrush::contract(env = lucky::traits::dapps_staking::CustomEnvironment)]
 pub mod raffle_contract {
+    use ink::codegen::{EmitEvent, Env};
     use ink::env::call::{ExecutionInput, Selector};
-    use ink::prelude::vec::Vec;
+    use ink::prelude::{boxed::Box, vec, vec::Vec};
     use openbrush::{modifiers, traits::{Storage}};
     use openbrush::contracts::access_control::{*, AccessControlError, DEFAULT_ADMIN_ROLE};
 
     use lucky::impls::{
         raffle, raffle::*,
         participant_manager, participant_manager::*,
-        participant_filter::filter_latest_winners, participant_filter::filter_latest_winners::*
+        merkle_participants, merkle_participants::*,
+        dapps_staking, dapps_staking::*,
+        participant_filter::filter_latest_winners, participant_filter::filter_latest_winners::*,
+        participant_filter::filter_registry, participant_filter::filter_registry::*,
+        participant_filter::filter_denylist, participant_filter::filter_denylist::*,
+        participant_filter::filter_registration, participant_filter::filter_registration::*,
+        participant_filter::filter_winner_cooldown, participant_filter::filter_winner_cooldown::*,
+        participant_filter::filter_winner_decay, participant_filter::filter_winner_decay::*,
+        participant_filter::filter_contract_accounts, participant_filter::filter_contract_accounts::*,
+        participant_filter::filter_external_registry, participant_filter::filter_external_registry::*,
     };
     use lucky::traits::random_generator::{RandomGeneratorRef};
 
@@ -26,13 +36,125 @@ pub mod raffle_contract {
         #[ink(topic)]
         contract: AccountId,
         #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
         era: u32,
         pending_rewards: Balance,
         nb_winners: u16,
-        nb_participants: u16,
+        nb_participants: u32,
+        total_value: Balance,
+    }
+
+    /// Event emitted when participants are added
+    #[ink(event)]
+    pub struct ParticipantsAdded {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        era: u32,
+        nb_accepted: u32,
+        nb_rejected: u32,
+        added_value: Balance,
         total_value: Balance,
     }
 
+    /// Event emitted when the weight of a participant is updated
+    #[ink(event)]
+    pub struct ParticipantUpdated {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        era: u32,
+        #[ink(topic)]
+        account: AccountId,
+        old_value: Balance,
+        new_value: Balance,
+    }
+
+    /// Event emitted when a participant is removed
+    #[ink(event)]
+    pub struct ParticipantRemoved {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        era: u32,
+        #[ink(topic)]
+        account: AccountId,
+        value: Balance,
+    }
+
+    /// Event emitted when the participants of an era are cleared
+    #[ink(event)]
+    pub struct DataCleared {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        era: u32,
+    }
+
+    /// Event emitted when a pool is created
+    #[ink(event)]
+    pub struct PoolCreated {
+        #[ink(topic)]
+        pool_id: u32,
+    }
+
+    /// Event emitted when a pool is closed
+    #[ink(event)]
+    pub struct PoolClosed {
+        #[ink(topic)]
+        pool_id: u32,
+    }
+
+    /// Event emitted when a raffle waiting for the random seed is cancelled
+    #[ink(event)]
+    pub struct RaffleCancelled {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        era: u32,
+        request_id: u32,
+    }
+
+    /// Event emitted when an account is added in the denylist
+    #[ink(event)]
+    pub struct AddedToDenylist {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when an account is removed from the denylist
+    #[ink(event)]
+    pub struct RemovedFromDenylist {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when an account is registered to participate in the raffles
+    #[ink(event)]
+    pub struct Registered {
+        #[ink(topic)]
+        account: AccountId,
+        era: u32,
+    }
+
+    /// Event emitted when an account is unregistered
+    #[ink(event)]
+    pub struct Unregistered {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when smart contracts are excluded from the participants of the pool
+    #[ink(event)]
+    pub struct ContractAccountsExcluded {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        era: u32,
+        accounts: Vec<AccountId>,
+    }
+
     /// Errors occurred in the contract
     #[derive(Debug, Eq, PartialEq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -49,8 +171,13 @@ pub mod raffle_contract {
         LuckyOracleAddressMissing,
         RandomGeneratorAddressMissing,
         DappsStakingDeveloperAddressMissing,
+        DappAddressMissing,
         RewardManagerAddressMissing,
+        SecretNotRevealed,
         ParticipantManagerError(ParticipantManagerError),
+        MerkleParticipantsError(MerkleParticipantsError),
+        ParticipantFilterError(ParticipantFilterError),
+        DappsStakingReaderError(DappsStakingReaderError),
     }
 
     /// convertor from AccessControlError to ContractError
@@ -74,6 +201,27 @@ pub mod raffle_contract {
         }
     }
 
+    /// convertor from MerkleParticipantsError to ContractError
+    impl From<MerkleParticipantsError> for ContractError {
+        fn from(error: MerkleParticipantsError) -> Self {
+            ContractError::MerkleParticipantsError(error)
+        }
+    }
+
+    /// convertor from ParticipantFilterError to ContractError
+    impl From<ParticipantFilterError> for ContractError {
+        fn from(error: ParticipantFilterError) -> Self {
+            ContractError::ParticipantFilterError(error)
+        }
+    }
+
+    /// convertor from DappsStakingReaderError to ContractError
+    impl From<DappsStakingReaderError> for ContractError {
+        fn from(error: DappsStakingReaderError) -> Self {
+            ContractError::DappsStakingReaderError(error)
+        }
+    }
+
 
     /// Contract storage
     #[ink(storage)]
@@ -88,8 +236,28 @@ pub mod raffle_contract {
         dapps_staking_developer_address: Option<AccountId>,
         random_generator_address: Option<AccountId>,
         reward_manager_address: Option<AccountId>,
+        /// if true, the secret committed for the era must be revealed when the raffle is run
+        reveal_required: bool,
         #[storage_field]
         filter_latest_winners: filter_latest_winners::Data,
+        #[storage_field]
+        merkle_participants: merkle_participants::Data,
+        #[storage_field]
+        filter_registry: filter_registry::Data,
+        #[storage_field]
+        filter_denylist: filter_denylist::Data,
+        #[storage_field]
+        filter_registration: filter_registration::Data,
+        #[storage_field]
+        filter_winner_cooldown: filter_winner_cooldown::Data,
+        #[storage_field]
+        filter_winner_decay: filter_winner_decay::Data,
+        #[storage_field]
+        filter_contract_accounts: filter_contract_accounts::Data,
+        #[storage_field]
+        filter_external_registry: filter_external_registry::Data,
+        /// address of the dApp registered in dApps Staking, the stakers of this dApp are the participants
+        dapp_address: Option<AccountId>,
     }
 
     impl Random for Contract {
@@ -99,11 +267,155 @@ pub mod raffle_contract {
             let random = RandomGeneratorRef::get_random_number(&random_generator_address, min, max)?;
             Ok(random)
         }
+
+        fn get_random_numbers(&mut self, count: u32, min: u128, max: u128) -> Result<Vec<u128>, RandomError> {
+            // get the random numbers in one call
+            let random_generator_address = self.random_generator_address.ok_or(RandomError::MissingAddress)?;
+            let randoms = RandomGeneratorRef::get_random_numbers(&random_generator_address, count, min, max)?;
+            Ok(randoms)
+        }
+
+        fn get_random_numbers_with_secret(&mut self, era: u32, secret: [u8; 32], count: u32, min: u128, max: u128) -> Result<Vec<u128>, RandomError> {
+            // reveal the secret and get the random numbers in one call
+            let random_generator_address = self.random_generator_address.ok_or(RandomError::MissingAddress)?;
+            let randoms = RandomGeneratorRef::get_random_numbers_with_secret(&random_generator_address, era, secret, count, min, max)?;
+            Ok(randoms)
+        }
+
+        fn commit_secret(&mut self, era: u32, commitment: Hash) -> Result<(), RandomError> {
+            // commit the secret in the random generator
+            let random_generator_address = self.random_generator_address.ok_or(RandomError::MissingAddress)?;
+            RandomGeneratorRef::commit_secret(&random_generator_address, era, commitment)?;
+            Ok(())
+        }
+
+        fn request_random_seed(&mut self, era: u32, secret: Option<[u8; 32]>) -> Result<u32, RandomError> {
+            // request the random seed to the oracle
+            let random_generator_address = self.random_generator_address.ok_or(RandomError::MissingAddress)?;
+            let request_id = RandomGeneratorRef::request_randomness(&random_generator_address, era, secret)?;
+            Ok(request_id)
+        }
+
+        fn get_random_seed(&mut self, request_id: u32) -> Result<[u8; 32], RandomError> {
+            // get the random seed provided by the oracle
+            let random_generator_address = self.random_generator_address.ok_or(RandomError::MissingAddress)?;
+            RandomGeneratorRef::get_randomness(&random_generator_address, request_id)
+                .ok_or(RandomError::RandomnessNotFulfilled)
+        }
     }
 
     impl ParticipantManager for Contract{}
+
+    impl participant_manager::Internal for Contract {
+        fn _emit_participants_added_event(&self, pool_id: u32, era: u32, nb_accepted: u32, nb_rejected: u32, added_value: Balance, total_value: Balance){
+            self.env().emit_event(ParticipantsAdded { pool_id, era, nb_accepted, nb_rejected, added_value, total_value });
+        }
+        fn _emit_participant_updated_event(&self, pool_id: u32, era: u32, account: AccountId, old_value: Balance, new_value: Balance){
+            self.env().emit_event(ParticipantUpdated { pool_id, era, account, old_value, new_value });
+        }
+        fn _emit_participant_removed_event(&self, pool_id: u32, era: u32, account: AccountId, value: Balance){
+            self.env().emit_event(ParticipantRemoved { pool_id, era, account, value });
+        }
+        fn _emit_data_cleared_event(&self, pool_id: u32, era: u32){
+            self.env().emit_event(DataCleared { pool_id, era });
+        }
+    }
+
     impl Raffle for Contract{}
+    impl MerkleParticipants for Contract{}
     impl FilterLatestWinners for Contract{}
+    impl FilterRegistry for Contract{}
+    impl FilterDenylist for Contract{}
+    impl FilterRegistration for Contract{}
+    impl FilterWinnerCooldown for Contract{}
+    impl FilterWinnerDecay for Contract{}
+    impl FilterContractAccounts for Contract{}
+    impl FilterExternalRegistry for Contract{}
+
+    impl filter_denylist::Internal for Contract {
+        fn _emit_added_to_denylist_event(&self, account: AccountId){
+            self.env().emit_event(AddedToDenylist { account });
+        }
+        fn _emit_removed_from_denylist_event(&self, account: AccountId){
+            self.env().emit_event(RemovedFromDenylist { account });
+        }
+    }
+
+    impl filter_registration::Internal for Contract {
+        fn _get_current_era(&self) -> u32 {
+            self.env().extension().read_current_era()
+        }
+        fn _emit_registered_event(&self, account: AccountId, era: u32){
+            self.env().emit_event(Registered { account, era });
+        }
+        fn _emit_unregistered_event(&self, account: AccountId){
+            self.env().emit_event(Unregistered { account });
+        }
+    }
+
+    impl filter_winner_cooldown::Internal for Contract {
+        fn _get_current_era(&self) -> u32 {
+            self.env().extension().read_current_era()
+        }
+    }
+
+    impl filter_external_registry::Internal for Contract {
+        fn _call_eligibility_registry(&mut self, registry: &EligibilityRegistry, account: AccountId) -> Result<bool, ParticipantFilterError> {
+            let call = ink::env::call::build_call::<Environment>()
+                .call(registry.address)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(registry.selector))
+                        .push_arg(account)
+                );
+            match registry.kind {
+                RegistryKind::Psp34BalanceOf => {
+                    let balance = call.returns::<u32>()
+                        .try_invoke()
+                        .map_err(|_| ParticipantFilterError::EligibilityRegistryCallError)?
+                        .map_err(|_| ParticipantFilterError::EligibilityRegistryCallError)?;
+                    Ok(balance > 0)
+                }
+                RegistryKind::IsEligible => {
+                    call.returns::<bool>()
+                        .try_invoke()
+                        .map_err(|_| ParticipantFilterError::EligibilityRegistryCallError)?
+                        .map_err(|_| ParticipantFilterError::EligibilityRegistryCallError)
+                }
+            }
+        }
+    }
+
+    impl DappsStakingReader for Contract{}
+
+    impl dapps_staking::Internal for Contract {
+        fn _read_current_era(&self) -> u32 {
+            self.env().extension().read_current_era()
+        }
+
+        fn _read_staked_amount_on_contract(&self, staker: AccountId, contract: AccountId) -> Balance {
+            self.env().extension().read_staked_amount_on_contract(staker, contract)
+        }
+    }
+
+    impl filter_contract_accounts::Internal for Contract {
+        fn _is_contract(&self, account: &AccountId) -> bool {
+            self.env().is_contract(account)
+        }
+    }
+
+    impl filter_registry::Internal for Contract {
+        fn _get_participant_filter(&self, filter: FilterId) -> Box<dyn ParticipantFilter + '_> {
+            match filter {
+                FilterId::LatestWinners => Box::new(&self.filter_latest_winners),
+                FilterId::Denylist => Box::new(&self.filter_denylist),
+                FilterId::Registration => Box::new(&self.filter_registration),
+                FilterId::WinnerCooldown => Box::new(&self.filter_winner_cooldown),
+                FilterId::WinnerDecay => Box::new(WinnerDecayFilter(self)),
+                FilterId::ContractAccounts => Box::new(ContractAccountsFilter(self)),
+                FilterId::ExternalRegistry => Box::new(&self.filter_external_registry),
+            }
+        }
+    }
     impl AccessControl for Contract{}
 
     impl Contract {
@@ -112,10 +424,13 @@ pub mod raffle_contract {
             dapps_staking_developer_address: AccountId,
             random_generator_address: AccountId,
             reward_manager_address: AccountId,
+            participants_page_size: u32,
+            max_participants: u32,
         ) -> Self {
             let mut instance = Self::default();
             let caller = instance.env().caller();
             instance._init_with_admin(caller);
+            instance._init_participant_manager(participants_page_size, max_participants).expect("Should init the participant manager");
             instance.grant_role(RAFFLE_MANAGER, caller).expect("Should grant the role RAFFLE_MANAGER");
             instance.grant_role(PARTICIPANT_MANAGER, caller).expect("Should grant the role PARTICIPANT_MANAGER");
             instance.grant_role(PARTICIPANT_FILTER_MANAGER, caller).expect("Should grant the role PARTICIPANT_FILTER_MANAGER");
@@ -125,45 +440,220 @@ pub mod raffle_contract {
             instance
         }
 
-        /// add participants in the raffle and applied the filters
+        /// Create a new pool with its own participants, ratio distribution and winners filter.
+        /// The ratio distribution must be set before running the raffles of this pool
+        #[ink(message)]
+        #[modifiers(only_role(DEFAULT_ADMIN_ROLE))]
+        pub fn create_pool(&mut self, pool_id: u32) -> Result<(), ContractError> {
+            self._create_pool(pool_id)?;
+            self.env().emit_event(PoolCreated { pool_id });
+            Ok(())
+        }
+
+        /// Close the pool: no participant can be added and no raffle can be run anymore in this pool.
+        /// The participants already stored can still be pruned
+        #[ink(message)]
+        #[modifiers(only_role(DEFAULT_ADMIN_ROLE))]
+        pub fn close_pool(&mut self, pool_id: u32) -> Result<(), ContractError> {
+            self._close_pool(pool_id)?;
+            self.env().emit_event(PoolClosed { pool_id });
+            Ok(())
+        }
+
+        /// add participants in the raffle of the pool for the given era and applied the chain of filters enabled for this pool
         /// a participant with a weight higher than another participant will have normally more chance to be selected in the raffle
         /// weight can represent the number of raffle tickets for this participant.
         /// weight can also represent the amount staked in dAppStaking, ...
         #[ink(message)]
-        pub fn add_participants_with_filters(&mut self, participants: Vec<(AccountId, Balance)>) -> Result<(), ContractError>{
+        #[modifiers(only_role(PARTICIPANT_MANAGER))]
+        pub fn add_participants_with_filters(&mut self, pool_id: u32, era: u32, participants: Vec<(AccountId, Balance)>) -> Result<(), ContractError>{
 
-            let mut parts = participants.clone();
+            // the thresholds are checked on the values given, before the weights are changed by the filters
+            let (participants, nb_dropped) = self._check_thresholds(pool_id, era, participants)?;
 
-            let mut i = 0;
-            while i < parts.len() {
-                let p_account_id = parts.get(i).unwrap().0;
-                if self._is_in_last_winners(&p_account_id) {
-                    parts.remove(i);
-                } else {
-                    i += 1;
-                }
+            // check the participants in the external registry before applying the filters
+            if self.get_filters(pool_id).contains(&FilterId::ExternalRegistry) {
+                self._refresh_eligibility(pool_id, era, &participants)?;
             }
 
-            self.add_participants(parts)?;
+            let (parts, excluded_accounts) = self._apply_filters(pool_id, era, participants);
+
+            self._add_participants(pool_id, era, parts, nb_dropped)?;
+
+            // report the smart contracts excluded by the filter
+            let excluded_contracts: Vec<AccountId> = excluded_accounts.into_iter()
+                .filter(|(_, filter)| *filter == FilterId::ContractAccounts)
+                .map(|(account, _)| account)
+                .collect();
+            if !excluded_contracts.is_empty() {
+                self.env().emit_event(ContractAccountsExcluded { pool_id, era, accounts: excluded_contracts });
+            }
             Ok(())
         }
 
+        /// add the stakers of the dApp as participants in the pool for the given era and applied the filters.
+        /// The era must be the current era of dApps Staking: the pages given at the end of an era are rejected
+        /// instead of being added in the next era.
+        /// The amounts staked on the dApp are read with the dApps Staking chain extension, they cannot be altered by the caller.
+        /// The chain extension cannot list the stakers: the accounts are given by pages by the participant manager,
+        /// a staker not given is not a participant. The accounts without stake are ignored
+        #[ink(message)]
+        #[modifiers(only_role(PARTICIPANT_MANAGER))]
+        pub fn add_participants_from_dapps_staking(&mut self, pool_id: u32, era: u32, stakers: Vec<AccountId>) -> Result<(), ContractError>{
+
+            let dapp_address = self.dapp_address.ok_or(ContractError::DappAddressMissing)?;
+            let participants = self._read_stakes(dapp_address, era, stakers)?;
+
+            self.add_participants_with_filters(pool_id, era, participants)
+        }
+
+
+        /// Run the raffle of the pool for the given era.
+        /// The secret committed for this era in the random generator is revealed in the same call as the draw
+        #[ink(message)]
+        #[modifiers(only_role(RAFFLE_MANAGER))]
+        pub fn run_raffle(&mut self, pool_id: u32, era: u32, rewards: Balance, secret: Option<[u8; 32]>) -> Result<(), ContractError> {
+
+            self.check_secret_provided(&secret)?;
 
+            // select the winners
+            let winners = self._run_raffle(pool_id, era, rewards, secret)?;
+
+            self.distribute_rewards(pool_id, era, rewards, winners)
+        }
+
+        /// Start the raffle of the pool for the given era and request the random seed to the oracle.
+        /// The participants of the era are locked and the secret committed for this era is revealed with the request.
+        /// The raffle will be finished when the seed is provided
         #[ink(message)]
         #[modifiers(only_role(RAFFLE_MANAGER))]
-        pub fn run_raffle(&mut self, era: u32, rewards: Balance) -> Result<(), ContractError> {
+        pub fn start_raffle(&mut self, pool_id: u32, era: u32, rewards: Balance, secret: Option<[u8; 32]>) -> Result<u32, ContractError> {
 
+            self.check_secret_provided(&secret)?;
 
+            let request_id = self._start_raffle(pool_id, era, rewards, secret)?;
+            Ok(request_id)
+        }
+
+        /// Finish the pending raffle of the pool with the random seed provided by the oracle
+        #[ink(message)]
+        #[modifiers(only_role(RAFFLE_MANAGER))]
+        pub fn finish_raffle(&mut self, pool_id: u32) -> Result<(), ContractError> {
             // select the winners
-            // initialize the empty list of randomly selected values
-            let winners = self._run_raffle(era, rewards)?;
+            let (pending_raffle, winners) = self._finish_raffle(pool_id)?;
+
+            self.distribute_rewards(pool_id, pending_raffle.era, pending_raffle.total_rewards, winners)
+        }
+
+        /// Commit the hash (keccak256) of the secret revealed with the draw of the given era.
+        /// The commitment is rejected once participants are added in this era.
+        /// This contract must be the only account with the role RANDOM_GENERATOR_MANAGER in the random generator
+        #[ink(message)]
+        #[modifiers(only_role(RAFFLE_MANAGER))]
+        pub fn commit_secret(&mut self, era: u32, commitment: Hash) -> Result<(), ContractError> {
+            self._commit_secret(era, commitment)?;
+            Ok(())
+        }
+
+        /// Cancel the pending raffle of the pool when the oracle has not provided the random seed in time.
+        /// The raffle can be started again for the same era
+        #[ink(message)]
+        #[modifiers(only_role(RAFFLE_MANAGER))]
+        pub fn cancel_raffle(&mut self, pool_id: u32) -> Result<(), ContractError> {
+            let pending_raffle = self._cancel_raffle(pool_id)?;
+            self.env().emit_event(RaffleCancelled { pool_id, era: pending_raffle.era, request_id: pending_raffle.request_id });
+            Ok(())
+        }
+
+        /// Run the raffle of the pool for the participants committed in the Merkle root of the given era.
+        /// The winning offsets are published and the rewards are kept by this contract
+        /// until the winners are proven.
+        /// The participant filters are not applied: they must be applied off-chain when the tree is built
+        #[ink(message)]
+        #[modifiers(only_role(RAFFLE_MANAGER))]
+        pub fn run_merkle_raffle(&mut self, pool_id: u32, era: u32, rewards: Balance, secret: Option<[u8; 32]>) -> Result<(), ContractError> {
+
+            self.check_secret_provided(&secret)?;
+
+            let participants_root = self.get_participants_root(pool_id, era)
+                .ok_or(ContractError::MerkleParticipantsError(MerkleParticipantsError::RootNotFound))?;
+            let total_value = participants_root.total_value;
+
+            // select the winning offsets
+            let offsets = self._draw_winning_offsets(pool_id, era, total_value, rewards, secret)?;
+            let nb_winners = offsets.len();
+            self._publish_winning_offsets(pool_id, era, offsets)?;
+
+            self.withdraw_rewards(rewards)?;
+
+            // emit event RaffleDone
+            self.env().emit_event(RaffleDone {
+                contract: self.env().caller(),
+                pool_id,
+                era,
+                nb_winners: nb_winners as u16,
+                nb_participants: participants_root.nb_participants,
+                total_value,
+                pending_rewards: rewards,
+            });
+
+            Ok(())
+        }
+
+        /// Prove the participant covering the winning offset at the given index with the leaf and the Merkle proof.
+        /// Can be called by the winner or by a relayer. The reward is credited in the reward manager
+        #[ink(message)]
+        pub fn prove_winner(&mut self, pool_id: u32, era: u32, index: u32, leaf: ParticipantLeaf, proof: Vec<ProofNode>) -> Result<(), ContractError> {
+
+            let winner = leaf.account;
+            let reward = self._prove_winner(pool_id, era, index, leaf, proof)?;
+
+            // save the winner
+            self._add_winner(pool_id, winner);
+            self._set_last_win_era(pool_id, winner, era);
+
+            self.fund_rewards(era, vec![(winner, reward)], reward)
+        }
+
+        fn check_secret_provided(&self, secret: &Option<[u8; 32]>) -> Result<(), ContractError> {
+            // check the secret committed for this era is given to be revealed with the draw
+            if self.reveal_required && secret.is_none() {
+                return Err(ContractError::SecretNotRevealed);
+            }
+            Ok(())
+        }
+
+        fn distribute_rewards(&mut self, pool_id: u32, era: u32, rewards: Balance, winners: Vec<(AccountId, Balance)>) -> Result<(), ContractError> {
+
             let nb_winners = winners.len();
 
             // save the winners
             for winner in &winners {
-                self._add_winner(winner.0);
+                self._add_winner(pool_id, winner.0);
+                self._set_last_win_era(pool_id, winner.0, era);
             }
 
+            self.withdraw_rewards(rewards)?;
+            self.fund_rewards(era, winners, rewards)?;
+
+            // TODO split rewards given and total rewards
+            let total_value = self.get_total_value_for_era(pool_id, era);
+
+            // emit event RaffleDone
+            self.env().emit_event(RaffleDone {
+                contract: self.env().caller(),
+                pool_id,
+                era,
+                nb_winners: nb_winners as u16,
+                nb_participants: self.get_nb_participants_for_era(pool_id, era),
+                total_value,
+                pending_rewards: rewards,
+            });
+
+            Ok(())
+        }
+
+        fn withdraw_rewards(&mut self, rewards: Balance) -> Result<(), ContractError> {
             // withdraw the rewards from developer dAppsStaking
             let dapps_staking_developer_address = self.dapps_staking_developer_address.ok_or(ContractError::DappsStakingDeveloperAddressMissing)?;
             ink::env::call::build_call::<Environment>()
@@ -175,8 +665,10 @@ pub mod raffle_contract {
                 .returns::<()>()
                 .invoke();
                 //.map_err(|_| ContractError::CrossContractCallError1)?;
+            Ok(())
+        }
 
-
+        fn fund_rewards(&mut self, era: u32, winners: Vec<(AccountId, Balance)>, rewards: Balance) -> Result<(), ContractError> {
             // set the list of winners and fund the rewards 
             let reward_manager_address = self.reward_manager_address.ok_or(ContractError::RewardManagerAddressMissing)?;
             ink::env::call::build_call::<Environment>()
@@ -190,23 +682,21 @@ pub mod raffle_contract {
                 .returns::<()>()
                 .invoke();
                 //.map_err(|_| ContractError::CrossContractCallError2)?;
+            Ok(())
+        }
 
-            // TODO split rewards given and total rewards
-            let total_value = self.get_total_value();
-
-            // emit event RaffleDone
-            self.env().emit_event(RaffleDone {
-                contract: self.env().caller(),
-                era,
-                nb_winners: nb_winners as u16,
-                nb_participants: self.get_nb_participants(),
-                total_value,
-                pending_rewards: rewards,
-            });
-
+        #[ink(message)]
+        #[modifiers(only_role(RAFFLE_MANAGER))]
+        pub fn set_reveal_required(&mut self, reveal_required: bool) -> Result<(), ContractError> {
+            self.reveal_required = reveal_required;
             Ok(())
         }
 
+        #[ink(message)]
+        pub fn is_reveal_required(&self) -> bool {
+            self.reveal_required
+        }
+
         #[ink(message)]
         pub fn get_role_raffle_manager(&self) -> RoleType {
             RAFFLE_MANAGER
@@ -234,6 +724,19 @@ pub mod raffle_contract {
             self.dapps_staking_developer_address
         }
 
+        /// Set the address of the dApp registered in dApps Staking, the amounts staked on this dApp are the weights of the participants
+        #[ink(message)]
+        #[modifiers(only_role(DEFAULT_ADMIN_ROLE))]
+        pub fn set_dapp_address(&mut self, address: AccountId) -> Result<(), ContractError> {
+            self.dapp_address = Some(address);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_dapp_address(&self) -> Option<AccountId> {
+            self.dapp_address
+        }
+
         #[ink(message)]
         #[modifiers(only_role(DEFAULT_ADMIN_ROLE))]
         pub fn set_random_generator_address(&mut self, address: AccountId) -> Result<(), ContractError> {
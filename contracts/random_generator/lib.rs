@@ -4,6 +4,10 @@
 #[openbrush::contract]
 pub mod random_generator {
 
+    use ink::codegen::{
+        EmitEvent,
+        Env,
+    };
     use openbrush::{
         modifiers,
         traits::Storage
@@ -19,6 +23,22 @@ pub mod random_generator {
         random_generator::*,
     };
 
+    /// Event emitted when the public key of the oracle is set
+    #[ink(event)]
+    pub struct OraclePublicKeySet {
+        public_key: [u8; 33],
+    }
+
+    /// Event emitted when the proof provided by the oracle is verified, with the randomness derived from this proof
+    #[ink(event)]
+    pub struct RandomnessVerified {
+        #[ink(topic)]
+        request_id: u32,
+        #[ink(topic)]
+        era: u32,
+        randomness: [u8; 32],
+    }
+
     /// Errors occurred in the contract
     #[derive(Debug, Eq, PartialEq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -77,6 +97,22 @@ pub mod random_generator {
             RANDOM_GENERATOR_MANAGER
         }
 
+        #[ink(message)]
+        pub fn get_role_random_generator_oracle(&self) -> RoleType {
+            RANDOM_GENERATOR_ORACLE
+        }
+
+    }
+
+    impl random_generator::Internal for Contract {
+
+        fn _emit_oracle_public_key_set_event(&self, public_key: [u8; 33]){
+            self.env().emit_event(OraclePublicKeySet { public_key });
+        }
+
+        fn _emit_randomness_verified_event(&self, request_id: u32, era: u32, randomness: [u8; 32]){
+            self.env().emit_event(RandomnessVerified { request_id, era, randomness });
+        }
     }
 
 }
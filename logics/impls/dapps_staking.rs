@@ -0,0 +1,26 @@
+use ink::prelude::vec::Vec;
+use openbrush::traits::{AccountId, Balance};
+
+pub use crate::traits::dapps_staking::*;
+
+impl<T> DappsStakingReader for T
+    where
+        T: Internal,
+{
+
+    default fn _read_stakes(&self, dapp_address: AccountId, era: u32, stakers: Vec<AccountId>) -> Result<Vec<(AccountId, Balance)>, DappsStakingReaderError> {
+        if era != self._read_current_era() {
+            return Err(DappsStakingReaderError::InvalidEra);
+        }
+
+        let mut stakes = Vec::with_capacity(stakers.len());
+        for staker in stakers {
+            let staked_amount = self._read_staked_amount_on_contract(staker, dapp_address);
+            if staked_amount > 0 {
+                stakes.push((staker, staked_amount));
+            }
+        }
+        Ok(stakes)
+    }
+
+}
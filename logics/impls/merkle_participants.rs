@@ -0,0 +1,243 @@
+use ink::env::hash::{Keccak256, HashOutput};
+use ink::prelude::vec::Vec;
+use openbrush::contracts::access_control::access_control;
+use openbrush::storage::Mapping;
+use openbrush::traits::{Balance, Storage};
+use scale::Encode;
+
+pub use crate::traits::merkle_participants::{
+    *,
+    MerkleParticipantsError::*,
+};
+
+use crate::impls::participant_manager::PARTICIPANT_MANAGER;
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+/// prefixes used to not confuse a leaf with an internal node of the tree
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    /// Merkle roots of the participants by pool and era
+    roots: Mapping<(u32, u32), ParticipantsRoot>,
+    /// weight offsets selected by the raffle by pool and era
+    winning_offsets: Mapping<(u32, u32), Vec<WinningOffset>>,
+}
+
+/// return the hash of the leaf
+pub fn hash_leaf(leaf: &ParticipantLeaf) -> [u8; 32] {
+    let mut input: Vec<u8> = Vec::new();
+    input.push(LEAF_PREFIX);
+    input.extend_from_slice(&leaf.encode());
+    let mut output = <Keccak256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Keccak256>(&input, &mut output);
+    output
+}
+
+/// return the hash of the parent node.
+/// The total weights of the children are hashed with them so that the root commits to the total weight of the tree
+pub fn hash_node(left: &[u8; 32], left_value: Balance, right: &[u8; 32], right_value: Balance) -> [u8; 32] {
+    let mut input: Vec<u8> = Vec::new();
+    input.push(NODE_PREFIX);
+    input.extend_from_slice(left);
+    input.extend_from_slice(&left_value.encode());
+    input.extend_from_slice(right);
+    input.extend_from_slice(&right_value.encode());
+    let mut output = <Keccak256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Keccak256>(&input, &mut output);
+    output
+}
+
+/// return the total weight of the tree if the proof links the leaf to the root.
+/// The start of the leaf must be the total weight of the leaves on its left
+pub fn verify_proof(root: &[u8; 32], leaf: &ParticipantLeaf, proof: &[ProofNode]) -> Option<Balance> {
+    let mut hash = hash_leaf(leaf);
+    let mut value = leaf.end.checked_sub(leaf.start)?;
+    // total weight of the leaves on the left of the current node
+    let mut start = leaf.start;
+    for sibling in proof {
+        hash = if sibling.is_left {
+            start = start.checked_sub(sibling.value)?;
+            hash_node(&sibling.hash, sibling.value, &hash, value)
+        } else {
+            hash_node(&hash, value, &sibling.hash, sibling.value)
+        };
+        value = value.checked_add(sibling.value)?;
+    }
+    if hash != *root || start != 0 {
+        return None;
+    }
+    Some(value)
+}
+
+impl<T> MerkleParticipants for T
+    where
+        T: Storage<Data>,
+        T: Storage<access_control::Data>,
+{
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_MANAGER))]
+    default fn set_participants_root(
+        &mut self,
+        pool_id: u32,
+        era: u32,
+        root: [u8; 32],
+        nb_participants: u32,
+        last_leaf: ParticipantLeaf,
+        proof: Vec<ProofNode>
+    ) -> Result<(), MerkleParticipantsError> {
+        if self.data::<Data>().roots.contains(&(pool_id, era)) {
+            return Err(RootAlreadySet);
+        }
+        // the leaf must be the last one of the tree: all its siblings are on its left
+        if proof.iter().any(|sibling| !sibling.is_left) {
+            return Err(InvalidProof);
+        }
+        // the last node of a level without sibling is promoted: the last leaf has a sibling for each bit set in its index
+        if nb_participants == 0 || proof.len() != (nb_participants - 1).count_ones() as usize {
+            return Err(InvalidProof);
+        }
+        // the total weight committed in the root is the end of the last leaf
+        let total_value = verify_proof(&root, &last_leaf, &proof).ok_or(InvalidProof)?;
+        self.data::<Data>().roots.insert(&(pool_id, era), &ParticipantsRoot { root, nb_participants, total_value });
+        Ok(())
+    }
+
+    default fn get_participants_root(&self, pool_id: u32, era: u32) -> Option<ParticipantsRoot> {
+        self.data::<Data>().roots.get(&(pool_id, era))
+    }
+
+    default fn get_winning_offsets(&self, pool_id: u32, era: u32) -> Vec<WinningOffset> {
+        self.data::<Data>().winning_offsets.get(&(pool_id, era)).unwrap_or_default()
+    }
+
+    default fn _publish_winning_offsets(&mut self, pool_id: u32, era: u32, offsets: Vec<(Balance, Balance)>) -> Result<(), MerkleParticipantsError> {
+        if !self.data::<Data>().roots.contains(&(pool_id, era)) {
+            return Err(RootNotFound);
+        }
+        if self.data::<Data>().winning_offsets.contains(&(pool_id, era)) {
+            return Err(OffsetsAlreadyPublished);
+        }
+        let winning_offsets: Vec<WinningOffset> = offsets.into_iter()
+            .map(|(offset, reward)| WinningOffset { offset, reward, winner: None })
+            .collect();
+        self.data::<Data>().winning_offsets.insert(&(pool_id, era), &winning_offsets);
+        Ok(())
+    }
+
+    default fn _prove_winner(
+        &mut self,
+        pool_id: u32,
+        era: u32,
+        index: u32,
+        leaf: ParticipantLeaf,
+        proof: Vec<ProofNode>
+    ) -> Result<Balance, MerkleParticipantsError> {
+
+        let root = self.data::<Data>().roots.get(&(pool_id, era)).ok_or(RootNotFound)?;
+        let mut winning_offsets = self.data::<Data>().winning_offsets.get(&(pool_id, era)).unwrap_or_default();
+        let winning_offset = winning_offsets.get_mut(index as usize).ok_or(OffsetNotFound)?;
+
+        if winning_offset.winner.is_some() {
+            return Err(OffsetAlreadyProven);
+        }
+
+        // the leaf must cover the winning offset
+        if winning_offset.offset <= leaf.start || winning_offset.offset > leaf.end {
+            return Err(OffsetNotCovered);
+        }
+
+        // the leaf must belong to the tree
+        if verify_proof(&root.root, &leaf, &proof) != Some(root.total_value) {
+            return Err(InvalidProof);
+        }
+
+        winning_offset.winner = Some(leaf.account);
+        let reward = winning_offset.reward;
+        self.data::<Data>().winning_offsets.insert(&(pool_id, era), &winning_offsets);
+
+        Ok(reward)
+    }
+
+}
+
+/// Merkle tree built off-chain with the participants of an era.
+/// Used to compute the root stored in the contract and the proofs sent by the winners
+#[cfg(feature = "std")]
+pub struct ParticipantsMerkleTree {
+    leaves: Vec<ParticipantLeaf>,
+    /// hashes and total weights of the nodes by level, starting with the leaves
+    layers: Vec<Vec<([u8; 32], Balance)>>,
+}
+
+#[cfg(feature = "std")]
+impl ParticipantsMerkleTree {
+
+    /// build the tree with the participants and their weights.
+    /// The leaves are built with the cumulative weights in the order of the participants
+    pub fn new(participants: &[(openbrush::traits::AccountId, Balance)]) -> Self {
+
+        let mut leaves = Vec::with_capacity(participants.len());
+        let mut total_value: Balance = 0;
+        for (account, value) in participants {
+            let start = total_value;
+            total_value += value;
+            leaves.push(ParticipantLeaf { account: *account, start, end: total_value });
+        }
+
+        let mut layers = Vec::new();
+        layers.push(leaves.iter().map(|leaf| (hash_leaf(leaf), leaf.end - leaf.start)).collect::<Vec<([u8; 32], Balance)>>());
+        while layers.last().unwrap().len() > 1 {
+            let layer = layers.last().unwrap();
+            // the last node without sibling is promoted to the next level
+            let next_layer = layer.chunks(2)
+                .map(|nodes| if nodes.len() == 2 {
+                    let ((left, left_value), (right, right_value)) = (nodes[0], nodes[1]);
+                    (hash_node(&left, left_value, &right, right_value), left_value + right_value)
+                } else {
+                    nodes[0]
+                })
+                .collect();
+            layers.push(next_layer);
+        }
+
+        ParticipantsMerkleTree { leaves, layers }
+    }
+
+    /// return the root of the tree (zero if there is no participant)
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().unwrap().first().map(|(hash, _)| *hash).unwrap_or_default()
+    }
+
+    pub fn total_value(&self) -> Balance {
+        self.leaves.last().map(|leaf| leaf.end).unwrap_or_default()
+    }
+
+    pub fn leaves(&self) -> &Vec<ParticipantLeaf> {
+        &self.leaves
+    }
+
+    /// return the index of the leaf covering the given offset
+    pub fn find_leaf(&self, offset: Balance) -> Option<usize> {
+        let index = self.leaves.partition_point(|leaf| leaf.end < offset);
+        self.leaves.get(index).filter(|leaf| leaf.start < offset).map(|_| index)
+    }
+
+    /// return the proof (siblings from the leaf to the root) for the leaf at the given index
+    pub fn get_proof(&self, index: usize) -> Vec<ProofNode> {
+        let mut proof = Vec::new();
+        let mut position = index;
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let sibling = position ^ 1;
+            if sibling < layer.len() {
+                let (hash, value) = layer[sibling];
+                proof.push(ProofNode { hash, value, is_left: sibling < position });
+            }
+            position /= 2;
+        }
+        proof
+    }
+}
@@ -0,0 +1,56 @@
+use openbrush::contracts::access_control::access_control;
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, Storage};
+use ink::prelude::vec::Vec;
+use crate::traits::participant_filter::participant_filter::{ParticipantFilter, ParticipantFilterError};
+
+pub use crate::traits::participant_filter::filter_contract_accounts::*;
+
+use crate::impls::participant_filter::filter_latest_winners::PARTICIPANT_FILTER_MANAGER;
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    /// smart contracts allowed to participate in the raffles
+    allowlist: Mapping<AccountId, ()>,
+}
+
+/// Filter checking the accounts with the environment of the contract
+pub struct ContractAccountsFilter<'a, T>(pub &'a T);
+
+impl<T: FilterContractAccounts + Internal> ParticipantFilter for ContractAccountsFilter<'_, T> {
+    /// the smart contracts are not eligible, except the ones in the allowlist
+    fn is_eligible(&self, _pool_id: u32, _era: u32, account: &AccountId, _value: Balance) -> bool {
+        self.0.is_contract_allowed(*account) || !self.0._is_contract(account)
+    }
+}
+
+impl<T> FilterContractAccounts for T
+    where
+        T: Storage<Data>,
+        T: Storage<access_control::Data>,
+{
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_FILTER_MANAGER))]
+    default fn add_to_contract_allowlist(&mut self, accounts: Vec<AccountId>) -> Result<(), ParticipantFilterError> {
+        for account in accounts {
+            self.data::<Data>().allowlist.insert(&account, &());
+        }
+        Ok(())
+    }
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_FILTER_MANAGER))]
+    default fn remove_from_contract_allowlist(&mut self, accounts: Vec<AccountId>) -> Result<(), ParticipantFilterError> {
+        for account in accounts {
+            self.data::<Data>().allowlist.remove(&account);
+        }
+        Ok(())
+    }
+
+    default fn is_contract_allowed(&self, account: AccountId) -> bool {
+        self.data::<Data>().allowlist.contains(&account)
+    }
+
+}
@@ -0,0 +1,101 @@
+use openbrush::contracts::access_control::access_control;
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, Storage};
+use ink::prelude::vec::Vec;
+use crate::traits::participant_filter::participant_filter::{ParticipantFilter, ParticipantFilterError};
+
+pub use crate::traits::participant_filter::filter_denylist::*;
+
+use crate::impls::participant_filter::filter_latest_winners::PARTICIPANT_FILTER_MANAGER;
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    /// accounts excluded from the raffles
+    denylist: Mapping<AccountId, ()>,
+    /// number of denied accounts
+    nb_accounts: u32,
+    /// denied accounts by position (starting at 1), used to list them
+    accounts: Mapping<u32, AccountId>,
+    /// position of the denied accounts
+    positions: Mapping<AccountId, u32>,
+}
+
+impl ParticipantFilter for Data {
+    /// the denied accounts are not eligible
+    fn is_eligible(&self, _pool_id: u32, _era: u32, account: &AccountId, _value: Balance) -> bool {
+        !self.denylist.contains(account)
+    }
+}
+
+impl<T> FilterDenylist for T
+    where
+        T: Internal,
+        T: Storage<Data>,
+        T: Storage<access_control::Data>,
+{
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_FILTER_MANAGER))]
+    default fn add_to_denylist(&mut self, accounts: Vec<AccountId>) -> Result<(), ParticipantFilterError> {
+        for account in accounts {
+            if self.data::<Data>().denylist.contains(&account) {
+                continue;
+            }
+            let position = self.data::<Data>().nb_accounts + 1;
+            self.data::<Data>().denylist.insert(&account, &());
+            self.data::<Data>().accounts.insert(&position, &account);
+            self.data::<Data>().positions.insert(&account, &position);
+            self.data::<Data>().nb_accounts = position;
+            self._emit_added_to_denylist_event(account);
+        }
+        Ok(())
+    }
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_FILTER_MANAGER))]
+    default fn remove_from_denylist(&mut self, accounts: Vec<AccountId>) -> Result<(), ParticipantFilterError> {
+        for account in accounts {
+            let position = match self.data::<Data>().positions.get(&account) {
+                Some(position) => position,
+                None => continue,
+            };
+            // move the last account at the position of the removed account
+            let last_position = self.data::<Data>().nb_accounts;
+            if position != last_position {
+                if let Some(last_account) = self.data::<Data>().accounts.get(&last_position) {
+                    self.data::<Data>().accounts.insert(&position, &last_account);
+                    self.data::<Data>().positions.insert(&last_account, &position);
+                }
+            }
+            self.data::<Data>().accounts.remove(&last_position);
+            self.data::<Data>().positions.remove(&account);
+            self.data::<Data>().denylist.remove(&account);
+            self.data::<Data>().nb_accounts = last_position - 1;
+            self._emit_removed_from_denylist_event(account);
+        }
+        Ok(())
+    }
+
+    default fn is_denied(&self, account: AccountId) -> bool {
+        self.data::<Data>().denylist.contains(&account)
+    }
+
+    default fn get_nb_denied_accounts(&self) -> u32 {
+        self.data::<Data>().nb_accounts
+    }
+
+    default fn get_denied_accounts(&self, offset: u32, limit: u16) -> Vec<AccountId> {
+        let nb_accounts = self.data::<Data>().nb_accounts;
+        let end = nb_accounts.min(offset.saturating_add(limit as u32));
+        let mut accounts = Vec::new();
+        // positions start at 1
+        for position in offset.saturating_add(1)..=end {
+            if let Some(account) = self.data::<Data>().accounts.get(&position) {
+                accounts.push(account);
+            }
+        }
+        accounts
+    }
+
+}
@@ -0,0 +1,77 @@
+use openbrush::contracts::access_control::access_control;
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, Storage};
+use crate::traits::participant_filter::participant_filter::{ParticipantFilter, ParticipantFilterError};
+
+pub use crate::traits::participant_filter::filter_external_registry::*;
+
+use crate::impls::raffle::RAFFLE_MANAGER;
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    /// external registry by pool
+    registries: Mapping<u32, EligibilityRegistry>,
+    /// version of the registry by pool, incremented when the registry is set to ignore the results cached before
+    versions: Mapping<u32, u32>,
+    /// version of the registry and eligibility of the accounts by pool and era
+    cache: Mapping<(u32, u32, AccountId), (u32, bool)>,
+}
+
+/// return the eligibility cached with the current registry of the pool
+fn read_cached_eligibility(data: &Data, pool_id: u32, era: u32, account: &AccountId) -> Option<bool> {
+    let (version, eligible) = data.cache.get(&(pool_id, era, *account))?;
+    if version != data.versions.get(&pool_id).unwrap_or_default() {
+        return None;
+    }
+    Some(eligible)
+}
+
+impl ParticipantFilter for Data {
+    /// only the accounts checked as eligible by the external registry for the era are eligible
+    fn is_eligible(&self, pool_id: u32, era: u32, account: &AccountId, _value: Balance) -> bool {
+        read_cached_eligibility(self, pool_id, era, account).unwrap_or(false)
+    }
+}
+
+impl<T> FilterExternalRegistry for T
+    where
+        T: Internal,
+        T: Storage<Data>,
+        T: Storage<access_control::Data>,
+{
+
+    #[openbrush::modifiers(access_control::only_role(RAFFLE_MANAGER))]
+    default fn set_eligibility_registry(&mut self, pool_id: u32, address: AccountId, selector: [u8; 4], kind: RegistryKind) -> Result<(), ParticipantFilterError> {
+        let version = self.data::<Data>().versions.get(&pool_id).unwrap_or_default();
+        self.data::<Data>().versions.insert(&pool_id, &version.wrapping_add(1));
+        self.data::<Data>().registries.insert(&pool_id, &EligibilityRegistry { address, selector, kind });
+        Ok(())
+    }
+
+    default fn get_eligibility_registry(&self, pool_id: u32) -> Option<EligibilityRegistry> {
+        self.data::<Data>().registries.get(&pool_id)
+    }
+
+    default fn get_cached_eligibility(&self, pool_id: u32, era: u32, account: AccountId) -> Option<bool> {
+        read_cached_eligibility(self.data::<Data>(), pool_id, era, &account)
+    }
+
+    default fn _refresh_eligibility(&mut self, pool_id: u32, era: u32, participants: &[(AccountId, Balance)]) -> Result<(), ParticipantFilterError> {
+        let registry = self.data::<Data>().registries.get(&pool_id)
+            .ok_or(ParticipantFilterError::EligibilityRegistryMissing)?;
+        let version = self.data::<Data>().versions.get(&pool_id).unwrap_or_default();
+
+        for (account, _) in participants {
+            if read_cached_eligibility(self.data::<Data>(), pool_id, era, account).is_some() {
+                continue;
+            }
+            let eligible = self._call_eligibility_registry(&registry, *account)?;
+            self.data::<Data>().cache.insert(&(pool_id, era, *account), &(version, eligible));
+        }
+        Ok(())
+    }
+
+}
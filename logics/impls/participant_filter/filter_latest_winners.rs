@@ -1,8 +1,9 @@
 use openbrush::contracts::access_control::{access_control, RoleType};
-use openbrush::traits::{AccountId, Storage};
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, Storage};
 use ink::prelude::vec::Vec;
 use ink::prelude::collections::vec_deque::VecDeque;
-use crate::traits::participant_filter::participant_filter::ParticipantFilterError;
+use crate::traits::participant_filter::participant_filter::{ParticipantFilter, ParticipantFilterError};
 
 pub use crate::traits::participant_filter::filter_latest_winners::*;
 
@@ -12,9 +13,17 @@ pub const PARTICIPANT_FILTER_MANAGER: RoleType = ink::selector_id!("PARTICIPANT_
 #[derive(Default, Debug)]
 #[openbrush::upgradeable_storage(STORAGE_KEY)]
 pub struct Data {
-    nb_filtered_winners: u16,
-    /// last winners to exclude
-    last_winners: VecDeque<AccountId>
+    /// number of winners to exclude by pool
+    nb_filtered_winners: Mapping<u32, u16>,
+    /// last winners to exclude by pool
+    last_winners: Mapping<u32, VecDeque<AccountId>>,
+}
+
+impl ParticipantFilter for Data {
+    /// the last winners of the pool are not eligible
+    fn is_eligible(&self, pool_id: u32, _era: u32, account: &AccountId, _value: Balance) -> bool {
+        !self.last_winners.get(&pool_id).unwrap_or_default().contains(account)
+    }
 }
 
 impl<T> FilterLatestWinners for T
@@ -24,32 +33,33 @@ impl<T> FilterLatestWinners for T
 {
 
     #[openbrush::modifiers(access_control::only_role(PARTICIPANT_FILTER_MANAGER))]
-    default fn set_nb_winners_filtered(&mut self, nb_filtered_winners: u16) -> Result<(), ParticipantFilterError> {
-        self.data::<Data>().nb_filtered_winners = nb_filtered_winners;
+    default fn set_nb_winners_filtered(&mut self, pool_id: u32, nb_filtered_winners: u16) -> Result<(), ParticipantFilterError> {
+        self.data::<Data>().nb_filtered_winners.insert(&pool_id, &nb_filtered_winners);
         Ok(())
     }
 
-    default fn get_nb_winners_filtered(&self) -> u16 {
-        self.data::<Data>().nb_filtered_winners
+    default fn get_nb_winners_filtered(&self, pool_id: u32) -> u16 {
+        self.data::<Data>().nb_filtered_winners.get(&pool_id).unwrap_or_default()
     }
 
-    default fn _add_winner(&mut self, winner: AccountId) {
+    default fn _add_winner(&mut self, pool_id: u32, winner: AccountId) {
+        let mut last_winners = self.data::<Data>().last_winners.get(&pool_id).unwrap_or_default();
         // add the last winner in the back
-        self.data::<Data>().last_winners.push_back(winner);
-        if self.data::<Data>().last_winners.len() > self.data::<Data>().nb_filtered_winners as usize {
+        last_winners.push_back(winner);
+        if last_winners.len() > self.get_nb_winners_filtered(pool_id) as usize {
             // remove the oldest winner (from the front)
-            self.data::<Data>().last_winners.pop_front();
+            last_winners.pop_front();
         }
+        self.data::<Data>().last_winners.insert(&pool_id, &last_winners);
     }
 
-    default fn get_last_winners(&self) -> Vec<AccountId> {
-        Vec::from(self.data::<Data>().last_winners.clone())
+    default fn get_last_winners(&self, pool_id: u32) -> Vec<AccountId> {
+        Vec::from(self.data::<Data>().last_winners.get(&pool_id).unwrap_or_default())
     }
 
-    default fn _is_in_last_winners(&self, participant: &AccountId) -> bool {
-        self.data::<Data>().last_winners.contains(participant)
+    default fn _is_in_last_winners(&self, pool_id: u32, participant: &AccountId) -> bool {
+        self.data::<Data>().last_winners.get(&pool_id).unwrap_or_default().contains(participant)
     }
 
 }
 
-
@@ -0,0 +1,79 @@
+use openbrush::contracts::access_control::access_control;
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, Storage};
+use ink::prelude::vec::Vec;
+use crate::traits::participant_filter::participant_filter::{ParticipantFilter, ParticipantFilterError};
+
+pub use crate::traits::participant_filter::filter_registration::*;
+
+use crate::impls::participant_filter::filter_latest_winners::PARTICIPANT_FILTER_MANAGER;
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    /// era when the accounts have been registered
+    registrations: Mapping<AccountId, u32>,
+}
+
+impl ParticipantFilter for Data {
+    /// only the accounts registered before the given era are eligible
+    fn is_eligible(&self, _pool_id: u32, era: u32, account: &AccountId, _value: Balance) -> bool {
+        match self.registrations.get(account) {
+            Some(registration_era) => registration_era < era,
+            None => false,
+        }
+    }
+}
+
+impl<T> FilterRegistration for T
+    where
+        T: Internal,
+        T: Storage<Data>,
+        T: Storage<access_control::Data>,
+{
+
+    default fn register(&mut self) -> Result<(), ParticipantFilterError> {
+        let caller = Self::env().caller();
+        if self.data::<Data>().registrations.contains(&caller) {
+            return Err(ParticipantFilterError::AlreadyRegistered);
+        }
+        let era = self._get_current_era();
+        self.data::<Data>().registrations.insert(&caller, &era);
+        self._emit_registered_event(caller, era);
+        Ok(())
+    }
+
+    default fn unregister(&mut self) -> Result<(), ParticipantFilterError> {
+        let caller = Self::env().caller();
+        if !self.data::<Data>().registrations.contains(&caller) {
+            return Err(ParticipantFilterError::NotRegistered);
+        }
+        self.data::<Data>().registrations.remove(&caller);
+        self._emit_unregistered_event(caller);
+        Ok(())
+    }
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_FILTER_MANAGER))]
+    default fn register_accounts(&mut self, accounts: Vec<AccountId>) -> Result<(), ParticipantFilterError> {
+        let era = self._get_current_era();
+        for account in accounts {
+            if self.data::<Data>().registrations.contains(&account) {
+                continue;
+            }
+            self.data::<Data>().registrations.insert(&account, &era);
+            self._emit_registered_event(account, era);
+        }
+        Ok(())
+    }
+
+    default fn is_registered(&self, account: AccountId) -> bool {
+        self.data::<Data>().registrations.contains(&account)
+    }
+
+    default fn get_registration_era(&self, account: AccountId) -> Option<u32> {
+        self.data::<Data>().registrations.get(&account)
+    }
+
+}
@@ -0,0 +1,89 @@
+use ink::prelude::boxed::Box;
+use ink::prelude::vec;
+use ink::prelude::vec::Vec;
+use openbrush::contracts::access_control::access_control;
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, Storage};
+
+pub use crate::traits::participant_filter::filter_registry::*;
+pub use crate::traits::participant_filter::participant_filter::*;
+
+use crate::impls::participant_filter::filter_latest_winners::PARTICIPANT_FILTER_MANAGER;
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    /// filters enabled by pool in the order they are applied
+    filters: Mapping<u32, Vec<FilterId>>,
+}
+
+impl<T> FilterRegistry for T
+    where
+        T: Internal,
+        T: Storage<Data>,
+        T: Storage<access_control::Data>,
+{
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_FILTER_MANAGER))]
+    default fn enable_filter(&mut self, pool_id: u32, filter: FilterId) -> Result<(), ParticipantFilterError> {
+        let mut filters = self.get_filters(pool_id);
+        if filters.contains(&filter) {
+            return Err(ParticipantFilterError::FilterAlreadyEnabled);
+        }
+        filters.push(filter);
+        self.data::<Data>().filters.insert(&pool_id, &filters);
+        Ok(())
+    }
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_FILTER_MANAGER))]
+    default fn disable_filter(&mut self, pool_id: u32, filter: FilterId) -> Result<(), ParticipantFilterError> {
+        let mut filters = self.get_filters(pool_id);
+        let index = filters.iter().position(|f| *f == filter).ok_or(ParticipantFilterError::FilterNotEnabled)?;
+        filters.remove(index);
+        self.data::<Data>().filters.insert(&pool_id, &filters);
+        Ok(())
+    }
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_FILTER_MANAGER))]
+    default fn set_filters(&mut self, pool_id: u32, filters: Vec<FilterId>) -> Result<(), ParticipantFilterError> {
+        for (i, filter) in filters.iter().enumerate() {
+            if filters[..i].contains(filter) {
+                return Err(ParticipantFilterError::DuplicateFilter);
+            }
+        }
+        self.data::<Data>().filters.insert(&pool_id, &filters);
+        Ok(())
+    }
+
+    default fn get_filters(&self, pool_id: u32) -> Vec<FilterId> {
+        // the latest winners are excluded while the filters of the pool have never been set
+        self.data::<Data>().filters.get(&pool_id).unwrap_or_else(|| vec![FilterId::LatestWinners])
+    }
+
+    default fn _apply_filters(&self, pool_id: u32, era: u32, participants: Vec<(AccountId, Balance)>) -> (Vec<(AccountId, Balance)>, Vec<(AccountId, FilterId)>) {
+
+        let filters: Vec<(FilterId, Box<dyn ParticipantFilter + '_>)> = self.get_filters(pool_id).into_iter()
+            .map(|filter| (filter, self._get_participant_filter(filter)))
+            .collect();
+        let mut eligible_participants = Vec::with_capacity(participants.len());
+        let mut excluded_accounts = Vec::new();
+
+        'participants: for (account, value) in participants {
+            let mut weight = value;
+            for (filter, participant_filter) in &filters {
+                if !participant_filter.is_eligible(pool_id, era, &account, weight) {
+                    // the participant is removed, the next filters are not applied
+                    excluded_accounts.push((account, *filter));
+                    continue 'participants;
+                }
+                weight = participant_filter.get_weight(pool_id, era, &account, weight);
+            }
+            eligible_participants.push((account, weight));
+        }
+
+        (eligible_participants, excluded_accounts)
+    }
+
+}
@@ -0,0 +1,74 @@
+use openbrush::contracts::access_control::access_control;
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, Storage};
+use crate::traits::participant_filter::participant_filter::{ParticipantFilter, ParticipantFilterError};
+
+pub use crate::traits::participant_filter::filter_winner_cooldown::*;
+
+use crate::impls::participant_filter::filter_latest_winners::PARTICIPANT_FILTER_MANAGER;
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    /// number of eras when the winners are excluded by pool
+    nb_cooldown_eras: Mapping<u32, u32>,
+    /// last era when the account won a raffle by pool
+    last_win_eras: Mapping<(u32, AccountId), u32>,
+}
+
+/// return the first era when the account can participate again in the raffles of the pool
+fn get_next_eligible_era(data: &Data, pool_id: u32, account: &AccountId) -> Option<u32> {
+    let last_win_era = data.last_win_eras.get(&(pool_id, *account))?;
+    let nb_cooldown_eras = data.nb_cooldown_eras.get(&pool_id).unwrap_or_default();
+    Some(last_win_era.saturating_add(nb_cooldown_eras).saturating_add(1))
+}
+
+impl ParticipantFilter for Data {
+    /// the winners of the pool are not eligible during the cooldown following the era of their win
+    fn is_eligible(&self, pool_id: u32, era: u32, account: &AccountId, _value: Balance) -> bool {
+        match get_next_eligible_era(self, pool_id, account) {
+            Some(next_eligible_era) => era >= next_eligible_era,
+            None => true,
+        }
+    }
+}
+
+impl<T> FilterWinnerCooldown for T
+    where
+        T: Internal,
+        T: Storage<Data>,
+        T: Storage<access_control::Data>,
+{
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_FILTER_MANAGER))]
+    default fn set_nb_cooldown_eras(&mut self, pool_id: u32, nb_cooldown_eras: u32) -> Result<(), ParticipantFilterError> {
+        self.data::<Data>().nb_cooldown_eras.insert(&pool_id, &nb_cooldown_eras);
+        Ok(())
+    }
+
+    default fn get_nb_cooldown_eras(&self, pool_id: u32) -> u32 {
+        self.data::<Data>().nb_cooldown_eras.get(&pool_id).unwrap_or_default()
+    }
+
+    default fn _set_last_win_era(&mut self, pool_id: u32, winner: AccountId, era: u32) {
+        // keep the most recent era if the raffles are not run in order
+        let last_win_era = self.data::<Data>().last_win_eras.get(&(pool_id, winner)).unwrap_or_default();
+        if era >= last_win_era {
+            self.data::<Data>().last_win_eras.insert(&(pool_id, winner), &era);
+        }
+    }
+
+    default fn get_last_win_era(&self, pool_id: u32, account: AccountId) -> Option<u32> {
+        self.data::<Data>().last_win_eras.get(&(pool_id, account))
+    }
+
+    default fn get_cooldown_remaining(&self, pool_id: u32, account: AccountId) -> u32 {
+        match get_next_eligible_era(self.data::<Data>(), pool_id, &account) {
+            Some(next_eligible_era) => next_eligible_era.saturating_sub(self._get_current_era()),
+            None => 0,
+        }
+    }
+
+}
@@ -0,0 +1,85 @@
+use openbrush::contracts::access_control::access_control;
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, Storage};
+use ink::prelude::vec::Vec;
+use crate::traits::participant_filter::participant_filter::{ParticipantFilter, ParticipantFilterError};
+
+pub use crate::traits::participant_filter::filter_winner_decay::*;
+
+use crate::impls::participant_filter::filter_latest_winners::PARTICIPANT_FILTER_MANAGER;
+use crate::impls::participant_filter::filter_winner_cooldown::FilterWinnerCooldown;
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+/// max percentage in the decay schedule: the weight is not reduced
+pub const MAX_DECAY_PERCENTAGE: u16 = 100;
+
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    /// percentages of the weight kept by the winners in the eras following their win by pool
+    decay_schedule: Mapping<u32, Vec<u16>>,
+}
+
+/// apply the percentage on the weight without overflow
+fn apply_percentage(value: Balance, percentage: u16) -> Balance {
+    let percentage = percentage as Balance;
+    let max = MAX_DECAY_PERCENTAGE as Balance;
+    value / max * percentage + value % max * percentage / max
+}
+
+/// reduce the weight based on the number of eras since the last win in the pool
+fn get_decayed_weight(data: &Data, pool_id: u32, era: u32, last_win_era: Option<u32>, value: Balance) -> Balance {
+    let last_win_era = match last_win_era {
+        Some(last_win_era) if era > last_win_era => last_win_era,
+        _ => return value,
+    };
+    // the first percentage is applied on the era following the win
+    let index = (era - last_win_era - 1) as usize;
+    match data.decay_schedule.get(&pool_id).unwrap_or_default().get(index) {
+        Some(percentage) => apply_percentage(value, *percentage),
+        None => value,
+    }
+}
+
+/// Filter reading the last win eras saved by the cooldown filter of the contract
+pub struct WinnerDecayFilter<'a, T>(pub &'a T);
+
+impl<T: FilterWinnerDecay> ParticipantFilter for WinnerDecayFilter<'_, T> {
+    /// the winners stay eligible, only their weight is reduced
+    fn is_eligible(&self, _pool_id: u32, _era: u32, _account: &AccountId, _value: Balance) -> bool {
+        true
+    }
+
+    fn get_weight(&self, pool_id: u32, era: u32, account: &AccountId, value: Balance) -> Balance {
+        self.0._get_decayed_weight(pool_id, era, account, value)
+    }
+}
+
+impl<T> FilterWinnerDecay for T
+    where
+        T: FilterWinnerCooldown,
+        T: Storage<Data>,
+        T: Storage<access_control::Data>,
+{
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_FILTER_MANAGER))]
+    default fn set_decay_schedule(&mut self, pool_id: u32, decay_schedule: Vec<u16>) -> Result<(), ParticipantFilterError> {
+        if decay_schedule.iter().any(|percentage| *percentage > MAX_DECAY_PERCENTAGE) {
+            return Err(ParticipantFilterError::InvalidDecayPercentage);
+        }
+        self.data::<Data>().decay_schedule.insert(&pool_id, &decay_schedule);
+        Ok(())
+    }
+
+    default fn get_decay_schedule(&self, pool_id: u32) -> Vec<u16> {
+        self.data::<Data>().decay_schedule.get(&pool_id).unwrap_or_default()
+    }
+
+    default fn _get_decayed_weight(&self, pool_id: u32, era: u32, account: &AccountId, value: Balance) -> Balance {
+        // the wins are recorded by the cooldown filter
+        let last_win_era = self.get_last_win_era(pool_id, *account);
+        get_decayed_weight(self.data::<Data>(), pool_id, era, last_win_era, value)
+    }
+
+}
@@ -1 +1,8 @@
-pub mod filter_latest_winners;
\ No newline at end of file
+pub mod filter_latest_winners;
+pub mod filter_registry;
+pub mod filter_denylist;
+pub mod filter_registration;
+pub mod filter_winner_cooldown;
+pub mod filter_winner_decay;
+pub mod filter_contract_accounts;
+pub mod filter_external_registry;
\ No newline at end of file
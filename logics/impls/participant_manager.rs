@@ -1,6 +1,7 @@
+use ink::prelude::collections::{BTreeMap, BTreeSet};
 use ink::prelude::vec::Vec;
-use ink::storage::Lazy;
 use openbrush::contracts::access_control::{access_control, RoleType};
+use openbrush::storage::Mapping;
 use openbrush::traits::AccountId;
 use openbrush::traits::Balance;
 use openbrush::traits::Storage;
@@ -9,257 +10,867 @@ pub use crate::traits::participant_manager::*;
 
 pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
 pub const PARTICIPANT_MANAGER: RoleType = ink::selector_id!("PARTICIPANT_MANAGER");
-pub const MAX_PART_BY_VEC: usize = 300;
-pub const MAX_PART: usize = MAX_PART_BY_VEC * 6;
+/// upper bound of the max number of participants, the positions in the weights index never overflow below it
+pub const MAX_PARTICIPANTS_LIMIT: u32 = u32::MAX / 2;
+/// upper bound of the page size: a participant is encoded in 48 bytes and a page must fit in the static buffer (16 KiB)
+/// used by ink to write a value in the storage
+pub const MAX_PAGE_SIZE: u32 = 300;
+/// max number of pages of participants removed by a call of prune_eras
+pub const MAX_PRUNED_PAGES: u32 = 10;
 
 #[derive(Default, Debug)]
 #[openbrush::upgradeable_storage(STORAGE_KEY)]
 pub struct Data {
-    nb_participants: u16,
-    /// participants
-    /// to not reach max capacity size, we will split the participants in many vectors (max 300 participants by vector)
-    participants_1: Lazy<Vec<Participant>>,
-    total_value_1: Balance,
-    participants_2: Lazy<Vec<Participant>>,
-    total_value_2: Balance,
-    participants_3: Lazy<Vec<Participant>>,
-    total_value_3: Balance,
-    participants_4: Lazy<Vec<Participant>>,
-    total_value_4: Balance,
-    participants_5: Lazy<Vec<Participant>>,
-    total_value_5: Balance,
-    participants_6: Lazy<Vec<Participant>>,
-    total_value_6: Balance,
+    /// open pools (sorted)
+    pools: Vec<u32>,
+    /// last era for which participants have been added by pool
+    current_era: Mapping<u32, u32>,
+    /// eras with participants still stored by pool (sorted)
+    eras: Mapping<u32, Vec<u32>>,
+    /// number of participants by pool and era
+    nb_participants: Mapping<(u32, u32), u32>,
+    /// highest number of participants reached by pool and era,
+    /// the nodes of the weights index are only written until this position and on its ancestors
+    highest_positions: Mapping<(u32, u32), u32>,
+    /// max number of participants by page
+    page_size: u32,
+    /// max number of participants by pool and era
+    max_participants: u32,
+    /// participants by pool, era and page (starting at 1)
+    /// to not reach max capacity size, we will split the participants in many vectors (max page_size participants by vector)
+    participants: Mapping<(u32, u32, u32), Vec<Participant>>,
+    /// total value by pool and era
+    total_value: Mapping<(u32, u32), Balance>,
+    /// cumulative weights of the participants (Fenwick tree) by pool, era and position (starting at 1)
+    /// used to find the participant matching with a weight in O(log n) storage reads
+    weights_index: Mapping<(u32, u32, u32), Balance>,
+    /// positions of all entries of the participants by pool, era and account (sorted)
+    accounts_index: Mapping<(u32, u32, AccountId), Vec<u32>>,
+    /// number of distinct accounts by pool and era
+    nb_accounts: Mapping<(u32, u32), u32>,
+    /// weight of the participants (sum of all its entries) by pool, era and account
+    account_values: Mapping<(u32, u32, AccountId), Balance>,
+    /// sum of the values given for the participants, before the weight policy, by pool, era and account
+    raw_values: Mapping<(u32, u32, AccountId), Balance>,
+    /// how the accounts already added are managed
+    duplicate_mode: DuplicateMode,
+    /// transformation applied on the values of the participants
+    weight_policy: WeightPolicy,
+    /// min value (included) of the participants
+    min_value: Option<Balance>,
+    /// max value (included) of the participants
+    max_value: Option<Balance>,
+    /// how the participants with a value out of the thresholds are managed
+    threshold_mode: ThresholdMode,
+    /// last era locked for a raffle by pool: the participants of this era and the previous ones can't be changed
+    locked_era: Mapping<u32, u32>,
 }
 
-fn push_participants(index: usize, src: &Vec<(AccountId, Balance)>, dest: &mut Vec<Participant>) -> (usize, Balance)  {
+/// return the weight of a participant with the given value
+pub fn apply_weight_policy(weight_policy: &WeightPolicy, value: Balance) -> Balance {
+    match weight_policy {
+        WeightPolicy::Linear => value,
+        WeightPolicy::SquareRoot => integer_sqrt(value),
+        WeightPolicy::Cap(cap) => value.min(*cap),
+        WeightPolicy::Logarithmic => (Balance::BITS - value.leading_zeros()) as Balance,
+        WeightPolicy::Equal => if value > 0 { 1 } else { 0 },
+    }
+}
+
+/// return the floor of the square root (Newton's method)
+fn integer_sqrt(value: Balance) -> Balance {
+    if value < 2 {
+        return value;
+    }
+    // initial guess higher than the square root
+    let mut x = 1 << ((Balance::BITS - value.leading_zeros() + 1) / 2);
+    loop {
+        let y = (x + value / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// return the number of pages needed to store the given number of participants
+fn get_nb_pages(nb_participants: u32, page_size: u32) -> u32 {
+    if page_size == 0 {
+        return 0;
+    }
+    // round up without overflow
+    nb_participants / page_size + u32::from(nb_participants % page_size != 0)
+}
+
+fn lowest_bit(position: u32) -> u32 {
+    position & position.wrapping_neg()
+}
+
+/// add the value in the Fenwick tree for the participant at the given position
+fn add_weight(weights_index: &mut Mapping<(u32, u32, u32), Balance>, pool_id: u32, era: u32, size: u32, position: u32, value: Balance) {
+    update_weight(weights_index, pool_id, era, size, position, 0, value);
+}
+
+/// replace the value in the Fenwick tree for the participant at the given position.
+/// The empty nodes are removed from the storage
+fn update_weight(weights_index: &mut Mapping<(u32, u32, u32), Balance>, pool_id: u32, era: u32, size: u32, position: u32, old_value: Balance, new_value: Balance) {
+    let mut i = position;
+    while i <= size {
+        let weight = weights_index.get(&(pool_id, era, i)).unwrap_or_default() - old_value + new_value;
+        if weight == 0 {
+            weights_index.remove(&(pool_id, era, i));
+        } else {
+            weights_index.insert(&(pool_id, era, i), &weight);
+        }
+        i += lowest_bit(i);
+    }
+}
+
+/// return the sum of the weights of the participants from the first position to the given position
+fn get_cumulative_weight(weights_index: &Mapping<(u32, u32, u32), Balance>, pool_id: u32, era: u32, position: u32) -> Balance {
+    let mut weight = 0;
+    let mut i = position;
+    while i > 0 {
+        weight += weights_index.get(&(pool_id, era, i)).unwrap_or_default();
+        i -= lowest_bit(i);
+    }
+    weight
+}
+
+/// return the page and the index in this page for the given position
+fn get_page_and_index(position: u32, page_size: u32) -> (u32, usize) {
+    ((position - 1) / page_size + 1, ((position - 1) % page_size) as usize)
+}
+
+/// return the position of the first participant whose cumulative weight is superior or equal to the given weight
+fn find_position(weights_index: &Mapping<(u32, u32, u32), Balance>, pool_id: u32, era: u32, size: u32, weight: Balance) -> u32 {
+    find_position_excluding(weights_index, pool_id, era, size, weight, &[])
+}
+
+/// return the position of the first participant whose cumulative weight is superior or equal to the given weight
+/// when the weights of the excluded entries (position, value) are removed from the tree.
+/// The tree is not modified: the excluded weights are subtracted from the nodes read during the search
+fn find_position_excluding(
+    weights_index: &Mapping<(u32, u32, u32), Balance>,
+    pool_id: u32,
+    era: u32,
+    size: u32,
+    weight: Balance,
+    excluded: &[(u32, Balance)]
+) -> u32 {
+    let mut position = 0;
+    let mut remaining_weight = weight;
+    // highest power of two lower than the size of the tree
+    let mut step = 1 << (31 - size.leading_zeros());
+    while step > 0 {
+        let next_position = position + step;
+        if next_position <= size {
+            // this node covers the positions ]position, next_position]
+            let excluded_weight: Balance = excluded.iter()
+                .filter(|(p, _)| *p > position && *p <= next_position)
+                .map(|(_, value)| *value)
+                .sum();
+            let next_weight = weights_index.get(&(pool_id, era, next_position)).unwrap_or_default() - excluded_weight;
+            if next_weight < remaining_weight {
+                position = next_position;
+                remaining_weight -= next_weight;
+            }
+        }
+        step >>= 1;
+    }
+    position + 1
+}
 
-    let mut total_value = Balance::default();
+/// remove the nodes of the Fenwick tree at the positions ]from_position, to_position] and the nodes after them
+fn clear_weights(weights_index: &mut Mapping<(u32, u32, u32), Balance>, pool_id: u32, era: u32, size: u32, from_position: u32, to_position: u32) {
+    for i in from_position + 1..=to_position {
+        weights_index.remove(&(pool_id, era, i));
+    }
+    // the nodes after the last position covering the previous positions
+    let mut i = to_position;
+    while i > 0 && i <= size {
+        weights_index.remove(&(pool_id, era, i));
+        i += lowest_bit(i);
+    }
+}
 
-    let remaining_len = MAX_PART_BY_VEC - dest.len();
-    let mut end_index = index + remaining_len;
-    if end_index >= src.len(){
-        end_index = src.len();
+/// index the entry of the account at the given position
+fn add_position(data: &mut Data, pool_id: u32, era: u32, account: AccountId, position: u32) {
+    let mut positions = data.accounts_index.get(&(pool_id, era, account)).unwrap_or_default();
+    if positions.is_empty() {
+        let nb_accounts = data.nb_accounts.get(&(pool_id, era)).unwrap_or_default();
+        data.nb_accounts.insert(&(pool_id, era), &(nb_accounts + 1));
+    }
+    if let Err(index) = positions.binary_search(&position) {
+        positions.insert(index, position);
     }
+    data.accounts_index.insert(&(pool_id, era, account), &positions);
+}
 
-    for (account, value) in  src[index..end_index].iter() {
-        dest.push(Participant{account: *account, value: *value});
-        total_value += *value;
+/// remove the entry of the account at the given position from the index
+fn remove_position(data: &mut Data, pool_id: u32, era: u32, account: AccountId, position: u32) {
+    let mut positions = data.accounts_index.get(&(pool_id, era, account)).unwrap_or_default();
+    positions.retain(|p| *p != position);
+    if positions.is_empty() {
+        data.accounts_index.remove(&(pool_id, era, account));
+        data.account_values.remove(&(pool_id, era, account));
+        data.raw_values.remove(&(pool_id, era, account));
+        let nb_accounts = data.nb_accounts.get(&(pool_id, era)).unwrap_or_default();
+        data.nb_accounts.insert(&(pool_id, era), &nb_accounts.saturating_sub(1));
+    } else {
+        data.accounts_index.insert(&(pool_id, era, account), &positions);
     }
+}
 
-    (end_index - index, total_value)
+/// index the entry of the account moved from the old position to the new position.
+/// The account keeps its values: only the position of the entry changes
+fn move_position(data: &mut Data, pool_id: u32, era: u32, account: AccountId, old_position: u32, new_position: u32) {
+    let mut positions = data.accounts_index.get(&(pool_id, era, account)).unwrap_or_default();
+    positions.retain(|p| *p != old_position);
+    if let Err(index) = positions.binary_search(&new_position) {
+        positions.insert(index, new_position);
+    }
+    data.accounts_index.insert(&(pool_id, era, account), &positions);
+}
+
+/// add the value in the weight of the account
+fn add_account_value(data: &mut Data, pool_id: u32, era: u32, account: AccountId, value: Balance) -> Result<(), ParticipantManagerError> {
+    let account_value = data.account_values.get(&(pool_id, era, account)).unwrap_or_default()
+        .checked_add(value).ok_or(ParticipantManagerError::AddOverFlow)?;
+    data.account_values.insert(&(pool_id, era, account), &account_value);
+    Ok(())
 }
 
-/// Iterate on the participants, sum the values,
-/// and return the participant if the sum is superior to the given weight
-fn select_winner_matching_value(
-    participants: &Vec<Participant>,
-    selected_value : Balance
-) -> Option<AccountId> {
-    let mut total_value = 0;
-    for participant in participants {
-        total_value += participant.value;
-        if total_value >= selected_value {
-            return Some(participant.account);
+/// remove the value from the weight of the account
+fn sub_account_value(data: &mut Data, pool_id: u32, era: u32, account: AccountId, value: Balance) {
+    let account_value = data.account_values.get(&(pool_id, era, account)).unwrap_or_default();
+    data.account_values.insert(&(pool_id, era, account), &account_value.saturating_sub(value));
+}
+
+/// remove the entry at the given position, the last entry takes its position.
+/// return the removed entry
+fn remove_entry(data: &mut Data, pool_id: u32, era: u32, position: u32) -> Result<Participant, ParticipantManagerError> {
+
+    let page_size = data.page_size;
+    let max_participants = data.max_participants;
+    let last_position = data.nb_participants.get(&(pool_id, era)).unwrap_or_default();
+
+    // remove the last entry
+    let (last_page, _) = get_page_and_index(last_position, page_size);
+    let mut last_participants = data.participants.get(&(pool_id, era, last_page)).unwrap_or_default();
+    let last_participant = last_participants.pop().ok_or(ParticipantManagerError::ParticipantNotFound)?;
+    update_weight(&mut data.weights_index, pool_id, era, max_participants, last_position, last_participant.value, 0);
+
+    let removed = if position == last_position {
+        sub_account_value(data, pool_id, era, last_participant.account, last_participant.value);
+        remove_position(data, pool_id, era, last_participant.account, last_position);
+        last_participant
+    } else {
+        // move the last entry at the position of the removed entry
+        let (page, index) = get_page_and_index(position, page_size);
+        let last_account = last_participant.account;
+        let last_value = last_participant.value;
+        let removed;
+        if page == last_page {
+            let participant = last_participants.get_mut(index).ok_or(ParticipantManagerError::ParticipantNotFound)?;
+            removed = core::mem::replace(participant, last_participant);
+        } else {
+            let mut participants = data.participants.get(&(pool_id, era, page)).unwrap_or_default();
+            let participant = participants.get_mut(index).ok_or(ParticipantManagerError::ParticipantNotFound)?;
+            removed = core::mem::replace(participant, last_participant);
+            data.participants.insert(&(pool_id, era, page), &participants);
+        }
+        update_weight(&mut data.weights_index, pool_id, era, max_participants, position, removed.value, last_value);
+        sub_account_value(data, pool_id, era, removed.account, removed.value);
+        if removed.account == last_account {
+            // the account keeps its entry moved from the last position
+            remove_position(data, pool_id, era, last_account, last_position);
+        } else {
+            remove_position(data, pool_id, era, removed.account, position);
+            move_position(data, pool_id, era, last_account, last_position, position);
         }
+        removed
+    };
+
+    if last_participants.is_empty() {
+        data.participants.remove(&(pool_id, era, last_page));
+    } else {
+        data.participants.insert(&(pool_id, era, last_page), &last_participants);
+    }
+
+    data.nb_participants.insert(&(pool_id, era), &(last_position - 1));
+    let total_value = data.total_value.get(&(pool_id, era)).unwrap_or_default() - removed.value;
+    data.total_value.insert(&(pool_id, era), &total_value);
+
+    Ok(removed)
+}
+
+/// return true if the value is between the min and max values (both included)
+fn is_in_thresholds(data: &Data, value: Balance) -> bool {
+    value >= data.min_value.unwrap_or(Balance::MIN) && value <= data.max_value.unwrap_or(Balance::MAX)
+}
+
+/// return true if the participants of the given pool and era can't be changed anymore
+fn is_era_locked(data: &Data, pool_id: u32, era: u32) -> bool {
+    match data.locked_era.get(&pool_id) {
+        Some(locked_era) => era <= locked_era,
+        None => false,
     }
-    None
+}
+
+/// remove the participants stored for the given pool and era, starting with the last page.
+/// At most nb_pages pages are removed and the number of removed pages is subtracted from nb_pages.
+/// return true if all participants of this era are removed
+fn clear_era(data: &mut Data, pool_id: u32, era: u32, nb_pages: &mut u32) -> bool {
+
+    let page_size = data.page_size;
+    let max_participants = data.max_participants;
+    let mut nb_participants = data.nb_participants.get(&(pool_id, era)).unwrap_or_default();
+
+    // the nodes written by the participants removed before
+    let highest_position = data.highest_positions.get(&(pool_id, era)).unwrap_or_default().max(nb_participants);
+    clear_weights(&mut data.weights_index, pool_id, era, max_participants, nb_participants, highest_position);
+
+    while nb_participants > 0 {
+        if *nb_pages == 0 {
+            // the next pages will be removed later
+            data.nb_participants.insert(&(pool_id, era), &nb_participants);
+            data.highest_positions.insert(&(pool_id, era), &nb_participants);
+            return false;
+        }
+        let (page, _) = get_page_and_index(nb_participants, page_size);
+        for participant in data.participants.get(&(pool_id, era, page)).unwrap_or_default() {
+            data.accounts_index.remove(&(pool_id, era, participant.account));
+            data.account_values.remove(&(pool_id, era, participant.account));
+            data.raw_values.remove(&(pool_id, era, participant.account));
+        }
+        data.participants.remove(&(pool_id, era, page));
+        let first_position = (page - 1) * page_size;
+        clear_weights(&mut data.weights_index, pool_id, era, max_participants, first_position, nb_participants);
+        nb_participants = first_position;
+        *nb_pages -= 1;
+    }
+
+    data.nb_participants.remove(&(pool_id, era));
+    data.highest_positions.remove(&(pool_id, era));
+    data.nb_accounts.remove(&(pool_id, era));
+    data.total_value.remove(&(pool_id, era));
+    let mut eras = data.eras.get(&pool_id).unwrap_or_default();
+    eras.retain(|e| *e != era);
+    data.eras.insert(&pool_id, &eras);
 }
 
 
 impl<T> ParticipantManager for T
     where
+        T: Internal,
         T: Storage<Data>,
         T: Storage<access_control::Data>,
 {
 
-    default fn get_nb_participants(&self) -> u16 {
-        self.data::<Data>().nb_participants
-    }
-
-    default fn get_total_value(&self) -> Balance {
-        self.data::<Data>().total_value_1 + self.data::<Data>().total_value_2
-            + self.data::<Data>().total_value_3 + self.data::<Data>().total_value_4
-            + self.data::<Data>().total_value_5 + self.data::<Data>().total_value_6
-    }
-
-    default fn get_participant(&self, value: Balance) -> Option<AccountId> {
-
-        let mut to_value= self.data::<Data>().total_value_1;
-        if value <= to_value {
-            return select_winner_matching_value(
-                &self.data::<Data>().participants_1.get_or_default(),
-                value
-            );
-        }
-        let mut from_value = to_value;
-        to_value += self.data::<Data>().total_value_2;
-        if value <= to_value {
-            return select_winner_matching_value(
-                &self.data::<Data>().participants_2.get_or_default(),
-                value - from_value
-            );
-        }
-        from_value = to_value;
-        to_value += self.data::<Data>().total_value_3;
-        if value <= to_value {
-            return select_winner_matching_value(
-                &self.data::<Data>().participants_3.get_or_default(),
-                value - from_value
-            );
-        }
-        from_value = to_value;
-        to_value += self.data::<Data>().total_value_4;
-        if value <= to_value {
-            return select_winner_matching_value(
-                &self.data::<Data>().participants_4.get_or_default(),
-                value - from_value
-            );
-        }
-        from_value = to_value;
-        to_value += self.data::<Data>().total_value_5;
-        if value <= to_value {
-            return select_winner_matching_value(
-                &self.data::<Data>().participants_5.get_or_default(),
-                value - from_value
-            );
-        }
-        from_value = to_value;
-        to_value += self.data::<Data>().total_value_6;
-        if value <= to_value {
-            return select_winner_matching_value(
-                &self.data::<Data>().participants_6.get_or_default(),
-                value - from_value
-            );
-        }
-        None
-    }
-
-    default fn get_participants(&self, page: u8) -> Result<Vec<Participant>, ParticipantManagerError> {
-
-        let participants;
-        if page == 0 {
-            participants = Vec::new();
-        } else if page == 1 {
-            participants = self.data::<Data>().participants_1.get_or_default();
-        } else if page == 2 {
-            participants = self.data::<Data>().participants_2.get_or_default();
-        } else if page == 3 {
-            participants = self.data::<Data>().participants_3.get_or_default();
-        } else if page == 4 {
-            participants = self.data::<Data>().participants_4.get_or_default();
-        } else if page == 5 {
-            participants = self.data::<Data>().participants_5.get_or_default();
-        } else if page == 6 {
-            participants = self.data::<Data>().participants_6.get_or_default();
-        } else {
+    default fn _init_participant_manager(&mut self, page_size: u32, max_participants: u32) -> Result<(), ParticipantManagerError> {
+        if page_size == 0 || page_size > MAX_PAGE_SIZE {
+            return Err(ParticipantManagerError::InvalidPageSize);
+        }
+        if max_participants < page_size || max_participants > MAX_PARTICIPANTS_LIMIT {
+            return Err(ParticipantManagerError::InvalidMaxParticipants);
+        }
+        self.data::<Data>().page_size = page_size;
+        self.data::<Data>().max_participants = max_participants;
+        Ok(())
+    }
+
+    default fn get_page_size(&self) -> u32 {
+        self.data::<Data>().page_size
+    }
+
+    default fn get_max_participants(&self) -> u32 {
+        self.data::<Data>().max_participants
+    }
+
+    default fn _create_pool(&mut self, pool_id: u32) -> Result<(), ParticipantManagerError> {
+        let pools = &mut self.data::<Data>().pools;
+        match pools.binary_search(&pool_id) {
+            Ok(_) => Err(ParticipantManagerError::PoolAlreadyExists),
+            Err(index) => {
+                pools.insert(index, pool_id);
+                Ok(())
+            }
+        }
+    }
+
+    default fn _close_pool(&mut self, pool_id: u32) -> Result<(), ParticipantManagerError> {
+        let pools = &mut self.data::<Data>().pools;
+        let index = pools.binary_search(&pool_id).map_err(|_| ParticipantManagerError::PoolNotFound)?;
+        pools.remove(index);
+        Ok(())
+    }
+
+    default fn get_pools(&self) -> Vec<u32> {
+        self.data::<Data>().pools.clone()
+    }
+
+    default fn is_pool_open(&self, pool_id: u32) -> bool {
+        self.data::<Data>().pools.binary_search(&pool_id).is_ok()
+    }
+
+    default fn get_current_era(&self, pool_id: u32) -> u32 {
+        self.data::<Data>().current_era.get(&pool_id).unwrap_or_default()
+    }
+
+    default fn get_eras(&self, pool_id: u32) -> Vec<u32> {
+        self.data::<Data>().eras.get(&pool_id).unwrap_or_default()
+    }
+
+    default fn get_locked_era(&self, pool_id: u32) -> Option<u32> {
+        self.data::<Data>().locked_era.get(&pool_id)
+    }
+
+    default fn _lock_era(&mut self, pool_id: u32, era: u32) {
+        if !is_era_locked(self.data::<Data>(), pool_id, era) {
+            self.data::<Data>().locked_era.insert(&pool_id, &era);
+        }
+    }
+
+    default fn get_nb_participants(&self, pool_id: u32) -> u32 {
+        self.get_nb_participants_for_era(pool_id, self.get_current_era(pool_id))
+    }
+
+    default fn get_nb_participants_for_era(&self, pool_id: u32, era: u32) -> u32 {
+        self.data::<Data>().nb_participants.get(&(pool_id, era)).unwrap_or_default()
+    }
+
+    default fn get_nb_accounts_for_era(&self, pool_id: u32, era: u32) -> u32 {
+        self.data::<Data>().nb_accounts.get(&(pool_id, era)).unwrap_or_default()
+    }
+
+    default fn get_total_value(&self, pool_id: u32) -> Balance {
+        self.get_total_value_for_era(pool_id, self.get_current_era(pool_id))
+    }
+
+    default fn get_total_value_for_era(&self, pool_id: u32, era: u32) -> Balance {
+        self.data::<Data>().total_value.get(&(pool_id, era)).unwrap_or_default()
+    }
+
+    default fn get_participant(&self, pool_id: u32, value: Balance) -> Option<AccountId> {
+        self.get_participant_for_era(pool_id, self.get_current_era(pool_id), value)
+    }
+
+    default fn get_participant_for_era(&self, pool_id: u32, era: u32, value: Balance) -> Option<AccountId> {
+
+        let nb_participants = self.get_nb_participants_for_era(pool_id, era);
+        if nb_participants == 0 {
+            return None;
+        }
+
+        // find the position of the participant in the index
+        let position = find_position(
+            &self.data::<Data>().weights_index,
+            pool_id,
+            era,
+            self.data::<Data>().max_participants,
+            value
+        );
+        if position > nb_participants {
+            return None;
+        }
+
+        // load the page containing this participant
+        let (page, index) = get_page_and_index(position, self.data::<Data>().page_size);
+        let participants = self.data::<Data>().participants.get(&(pool_id, era, page))?;
+        participants.get(index).map(|participant| participant.account)
+    }
+
+    default fn get_participant_value(&self, pool_id: u32, account: AccountId) -> Option<Balance> {
+        let era = self.get_current_era(pool_id);
+        if !self.data::<Data>().accounts_index.contains(&(pool_id, era, account)) {
+            return None;
+        }
+        Some(self.data::<Data>().account_values.get(&(pool_id, era, account)).unwrap_or_default())
+    }
+
+    default fn _get_participant_excluding(&self, pool_id: u32, era: u32, value: Balance, excluded: &[AccountId]) -> Option<(AccountId, Balance)> {
+
+        let nb_participants = self.get_nb_participants_for_era(pool_id, era);
+        if nb_participants == 0 {
+            return None;
+        }
+        let page_size = self.data::<Data>().page_size;
+
+        // entries of the excluded accounts, their weight is removed from the index during the search
+        let mut excluded_entries = Vec::new();
+        for account in excluded {
+            for position in self.data::<Data>().accounts_index.get(&(pool_id, era, *account)).unwrap_or_default() {
+                let (page, index) = get_page_and_index(position, page_size);
+                let participants = self.data::<Data>().participants.get(&(pool_id, era, page))?;
+                excluded_entries.push((position, participants.get(index)?.value));
+            }
+        }
+
+        // find the position of the participant in the index
+        let position = find_position_excluding(
+            &self.data::<Data>().weights_index,
+            pool_id,
+            era,
+            self.data::<Data>().max_participants,
+            value,
+            &excluded_entries
+        );
+        if position > nb_participants {
+            return None;
+        }
+
+        let (page, index) = get_page_and_index(position, page_size);
+        let winner = self.data::<Data>().participants.get(&(pool_id, era, page))?.get(index)?.account;
+        // total weight of this participant (sum of all its entries)
+        let winner_value = self.data::<Data>().account_values.get(&(pool_id, era, winner)).unwrap_or_default();
+
+        Some((winner, winner_value))
+    }
+
+    default fn get_participants(&self, pool_id: u32, page: u32) -> Result<Vec<Participant>, ParticipantManagerError> {
+        self.get_participants_for_era(pool_id, self.get_current_era(pool_id), page)
+    }
+
+    default fn get_participants_for_era(&self, pool_id: u32, era: u32, page: u32) -> Result<Vec<Participant>, ParticipantManagerError> {
+
+        // the pages start at 1
+        let nb_pages = get_nb_pages(self.data::<Data>().max_participants, self.data::<Data>().page_size);
+        if page == 0 || page > nb_pages {
             return Err(ParticipantManagerError::PageNotFound);
         }
 
-        Ok(participants)
+        Ok(self.data::<Data>().participants.get(&(pool_id, era, page)).unwrap_or_default())
+    }
+
+    default fn get_participants_range(&self, pool_id: u32, offset: u32, limit: u16) -> (Vec<Participant>, u32) {
+
+        let era = self.get_current_era(pool_id);
+        let nb_participants = self.get_nb_participants_for_era(pool_id, era);
+        let page_size = self.data::<Data>().page_size;
+
+        let mut participants = Vec::new();
+        if offset >= nb_participants {
+            return (participants, nb_participants);
+        }
+
+        let end = nb_participants.min(offset.saturating_add(limit as u32));
+        // positions start at 1
+        let mut position = offset + 1;
+        while position <= end {
+            // load the page and take the participants in the range
+            let (page, index) = get_page_and_index(position, page_size);
+            let nb_taken = (page_size - index as u32).min(end - position + 1);
+            let page_participants = self.data::<Data>().participants.get(&(pool_id, era, page)).unwrap_or_default();
+            participants.extend(page_participants.into_iter().skip(index).take(nb_taken as usize));
+            position += nb_taken;
+        }
+
+        (participants, nb_participants)
+    }
+
+    default fn find_participant(&self, pool_id: u32, account: AccountId) -> Vec<ParticipantRange> {
+        let era = self.get_current_era(pool_id);
+        let positions = self.data::<Data>().accounts_index.get(&(pool_id, era, account)).unwrap_or_default();
+
+        let mut ranges = Vec::with_capacity(positions.len());
+        for position in positions {
+            let (page, index) = get_page_and_index(position, self.data::<Data>().page_size);
+            let value = match self.data::<Data>().participants.get(&(pool_id, era, page)).and_then(|p| p.get(index).map(|p| p.value)) {
+                Some(value) => value,
+                None => continue,
+            };
+            let start = get_cumulative_weight(&self.data::<Data>().weights_index, pool_id, era, position - 1);
+            ranges.push(ParticipantRange { account, value, start, end: start + value });
+        }
+        ranges
+    }
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_MANAGER))]
+    default fn add_participants(&mut self, pool_id: u32, era: u32, participants: Vec<(AccountId, Balance)>) -> Result<(u32, u32), ParticipantManagerError> {
+        let (participants, nb_dropped) = self._check_thresholds(pool_id, era, participants)?;
+        self._add_participants(pool_id, era, participants, nb_dropped)
+    }
+
+    default fn _check_thresholds(&self, pool_id: u32, era: u32, participants: Vec<(AccountId, Balance)>) -> Result<(Vec<(AccountId, Balance)>, u32), ParticipantManagerError> {
+        // check the values are in the thresholds, the thresholds are checked on the sum of the values given for each account
+        // so splitting the value in many entries does not bypass them
+        let nb_given = participants.len();
+        let mut participants_in_range = Vec::with_capacity(nb_given);
+        let mut new_raw_values = BTreeMap::new();
+        for (account, value) in participants {
+            let raw_value = match new_raw_values.get(&account) {
+                Some(raw_value) => *raw_value,
+                None => self.data::<Data>().raw_values.get(&(pool_id, era, account)).unwrap_or_default(),
+            };
+            let new_raw_value = raw_value.checked_add(value).ok_or(ParticipantManagerError::AddOverFlow)?;
+            if is_in_thresholds(self.data::<Data>(), new_raw_value) {
+                new_raw_values.insert(account, new_raw_value);
+                participants_in_range.push((account, value));
+            } else if self.data::<Data>().threshold_mode == ThresholdMode::Reject {
+                return Err(ParticipantManagerError::ValueOutOfRange);
+            }
+        }
+        let nb_dropped = (nb_given - participants_in_range.len()) as u32;
+        Ok((participants_in_range, nb_dropped))
     }
 
     #[openbrush::modifiers(access_control::only_role(PARTICIPANT_MANAGER))]
-    default fn add_participants(&mut self, participants: Vec<(AccountId, Balance)>) -> Result<(), ParticipantManagerError> {
-
-        let mut nb_participants = self.data::<Data>().nb_participants as usize;
-        let mut index = 0;
-
-        while index < participants.len() {
-
-            let inserted_participants;
-
-            if nb_participants < MAX_PART_BY_VEC {
-                let mut p = self.data::<Data>().participants_1.get_or_default();
-                let (nb_pushed, total_value) = push_participants(index, &participants, &mut p);
-                inserted_participants = nb_pushed;
-                self.data::<Data>().total_value_1 +=  total_value;
-                self.data::<Data>().participants_1.set(&p);
-            } else if nb_participants < 2 * MAX_PART_BY_VEC {
-                let mut p = self.data::<Data>().participants_2.get_or_default();
-                let (nb_pushed, total_value) = push_participants(index, &participants, &mut p);
-                inserted_participants = nb_pushed;
-                self.data::<Data>().total_value_2 +=  total_value;
-                self.data::<Data>().participants_2.set(&p);
-            } else if nb_participants < 3 * MAX_PART_BY_VEC {
-                let mut p = self.data::<Data>().participants_3.get_or_default();
-                let (nb_pushed, total_value) = push_participants(index, &participants, &mut p);
-                inserted_participants = nb_pushed;
-                self.data::<Data>().total_value_3 +=  total_value;
-                self.data::<Data>().participants_3.set(&p);
-            } else if nb_participants < 4 * MAX_PART_BY_VEC {
-                let mut p = self.data::<Data>().participants_4.get_or_default();
-                let (nb_pushed, total_value) = push_participants(index, &participants, &mut p);
-                inserted_participants = nb_pushed;
-                self.data::<Data>().total_value_4 +=  total_value;
-                self.data::<Data>().participants_4.set(&p);
-            } else if nb_participants < 5 * MAX_PART_BY_VEC {
-                let mut p = self.data::<Data>().participants_5.get_or_default();
-                let (nb_pushed, total_value) = push_participants(index, &participants, &mut p);
-                inserted_participants = nb_pushed;
-                self.data::<Data>().total_value_5 +=  total_value;
-                self.data::<Data>().participants_5.set(&p);
-            } else if nb_participants < 6 * MAX_PART_BY_VEC {
-                let mut p = self.data::<Data>().participants_6.get_or_default();
-                let (nb_pushed, total_value) = push_participants(index, &participants, &mut p);
-                inserted_participants = nb_pushed;
-                self.data::<Data>().total_value_6 +=  total_value;
-                self.data::<Data>().participants_6.set(&p);
+    default fn _add_participants(&mut self, pool_id: u32, era: u32, participants: Vec<(AccountId, Balance)>, nb_dropped: u32) -> Result<(u32, u32), ParticipantManagerError> {
+
+        if !self.is_pool_open(pool_id) {
+            return Err(ParticipantManagerError::PoolNotFound);
+        }
+
+        if is_era_locked(self.data::<Data>(), pool_id, era) {
+            return Err(ParticipantManagerError::EraLocked);
+        }
+
+        let page_size = self.data::<Data>().page_size;
+        let max_participants = self.data::<Data>().max_participants;
+        let duplicate_mode = self.data::<Data>().duplicate_mode;
+        let weight_policy = self.data::<Data>().weight_policy;
+        let mut nb_participants = self.get_nb_participants_for_era(pool_id, era);
+
+        // check the duplicates and the number of new entries before inserting the participants
+        let mut nb_new_entries = 0;
+        let mut new_accounts = BTreeSet::new();
+        for (account, _) in participants.iter() {
+            if duplicate_mode == DuplicateMode::Allow {
+                nb_new_entries += 1;
+            } else if self.data::<Data>().accounts_index.contains(&(pool_id, era, *account)) || !new_accounts.insert(*account) {
+                if duplicate_mode == DuplicateMode::Reject {
+                    return Err(ParticipantManagerError::DuplicateParticipant);
+                }
             } else {
-                return Err(ParticipantManagerError::MaxSizeExceeded);
+                nb_new_entries += 1;
             }
-            nb_participants = nb_participants + inserted_participants;
-            index = index + inserted_participants;
+        }
+        if nb_participants as usize + nb_new_entries > max_participants as usize {
+            return Err(ParticipantManagerError::MaxSizeExceeded);
+        }
+
+        let initial_total_value = self.get_total_value_for_era(pool_id, era);
+        let mut total_value = initial_total_value;
+
+        // page where the next participant will be pushed
+        let mut last_page = nb_participants / page_size + 1;
+        let mut last_participants = self.data::<Data>().participants.get(&(pool_id, era, last_page)).unwrap_or_default();
+
+        for (account, value) in participants.iter() {
+
+            // the weight policy is applied on the sum of the values given for this account,
+            // splitting the value in many entries gives the same weight
+            let raw_value = self.data::<Data>().raw_values.get(&(pool_id, era, *account)).unwrap_or_default();
+            let new_raw_value = raw_value.checked_add(*value).ok_or(ParticipantManagerError::AddOverFlow)?;
+            self.data::<Data>().raw_values.insert(&(pool_id, era, *account), &new_raw_value);
+            let value = apply_weight_policy(&weight_policy, new_raw_value)
+                .saturating_sub(apply_weight_policy(&weight_policy, raw_value));
+            total_value = total_value.checked_add(value).ok_or(ParticipantManagerError::AddOverFlow)?;
+
+            if duplicate_mode == DuplicateMode::Merge {
+                if let Some(&position) = self.data::<Data>().accounts_index.get(&(pool_id, era, *account)).unwrap_or_default().first() {
+                    // add the value in the existing entry
+                    let (page, index) = get_page_and_index(position, page_size);
+                    if page == last_page {
+                        last_participants[index].value = last_participants[index].value
+                            .checked_add(value).ok_or(ParticipantManagerError::AddOverFlow)?;
+                    } else {
+                        let mut p = self.data::<Data>().participants.get(&(pool_id, era, page)).unwrap_or_default();
+                        p[index].value = p[index].value.checked_add(value).ok_or(ParticipantManagerError::AddOverFlow)?;
+                        self.data::<Data>().participants.insert(&(pool_id, era, page), &p);
+                    }
+                    add_weight(&mut self.data::<Data>().weights_index, pool_id, era, max_participants, position, value);
+                    add_account_value(self.data::<Data>(), pool_id, era, *account, value)?;
+                    continue;
+                }
+            }
+
+            if last_participants.len() >= page_size as usize {
+                // the page is full, go to the next one
+                self.data::<Data>().participants.insert(&(pool_id, era, last_page), &last_participants);
+                last_page += 1;
+                last_participants = Vec::new();
+            }
+
+            last_participants.push(Participant{account: *account, value});
+            // update the indexes with the inserted participant
+            nb_participants += 1;
+            add_weight(&mut self.data::<Data>().weights_index, pool_id, era, max_participants, nb_participants, value);
+            add_position(self.data::<Data>(), pool_id, era, *account, nb_participants);
+            add_account_value(self.data::<Data>(), pool_id, era, *account, value)?;
+        }
+
+        if !last_participants.is_empty() {
+            self.data::<Data>().participants.insert(&(pool_id, era, last_page), &last_participants);
+        }
+
+        // keep the list of eras with participants
+        let mut eras = self.get_eras(pool_id);
+        if let Err(index) = eras.binary_search(&era) {
+            eras.insert(index, era);
+            self.data::<Data>().eras.insert(&pool_id, &eras);
+        }
+        if era > self.get_current_era(pool_id) {
+            self.data::<Data>().current_era.insert(&pool_id, &era);
+        }
 
+        self.data::<Data>().nb_participants.insert(&(pool_id, era), &nb_participants);
+        if nb_participants > self.data::<Data>().highest_positions.get(&(pool_id, era)).unwrap_or_default() {
+            self.data::<Data>().highest_positions.insert(&(pool_id, era), &nb_participants);
         }
-        self.data::<Data>().nb_participants = nb_participants as u16;
+        self.data::<Data>().total_value.insert(&(pool_id, era), &total_value);
+
+        let nb_accepted = participants.len() as u32;
+        self._emit_participants_added_event(pool_id, era, nb_accepted, nb_dropped, total_value - initial_total_value, total_value);
+        Ok((nb_accepted, nb_dropped))
+    }
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_MANAGER))]
+    default fn set_duplicate_mode(&mut self, duplicate_mode: DuplicateMode) -> Result<(), ParticipantManagerError> {
+        self.data::<Data>().duplicate_mode = duplicate_mode;
         Ok(())
     }
 
+    default fn get_duplicate_mode(&self) -> DuplicateMode {
+        self.data::<Data>().duplicate_mode
+    }
+
     #[openbrush::modifiers(access_control::only_role(PARTICIPANT_MANAGER))]
-    default fn clear_data(&mut self) -> Result<(), ParticipantManagerError> {
-
-        let nb_participants = self.data::<Data>().nb_participants as usize;
-        
-        if nb_participants > 0 {
-            let mut p = self.data::<Data>().participants_1.get_or_default();
-            p.clear();
-            self.data::<Data>().participants_1.set(&p);
-        } 
-        if nb_participants > MAX_PART_BY_VEC {
-            let mut p = self.data::<Data>().participants_2.get_or_default();
-            p.clear();
-            self.data::<Data>().participants_2.set(&p);
-        } 
-        if nb_participants > 2 * MAX_PART_BY_VEC {
-            let mut p = self.data::<Data>().participants_3.get_or_default();
-            p.clear();
-            self.data::<Data>().participants_3.set(&p);
-        } 
-        if nb_participants > 3 * MAX_PART_BY_VEC {
-            let mut p = self.data::<Data>().participants_4.get_or_default();
-            p.clear();
-            self.data::<Data>().participants_4.set(&p);
-        } 
-        if nb_participants > 4 * MAX_PART_BY_VEC {
-            let mut p = self.data::<Data>().participants_5.get_or_default();
-            p.clear();
-            self.data::<Data>().participants_5.set(&p);
-        } 
-        if nb_participants > 5 * MAX_PART_BY_VEC {
-            let mut p = self.data::<Data>().participants_6.get_or_default();
-            p.clear();
-            self.data::<Data>().participants_6.set(&p);
-        } 
-
-        self.data::<Data>().nb_participants = 0;
-        self.data::<Data>().total_value_1 = 0;
-        self.data::<Data>().total_value_2 = 0;
-        self.data::<Data>().total_value_3 = 0;
-        self.data::<Data>().total_value_4 = 0;
-        self.data::<Data>().total_value_5 = 0;
-        self.data::<Data>().total_value_6 = 0;
+    default fn set_weight_policy(&mut self, weight_policy: WeightPolicy) -> Result<(), ParticipantManagerError> {
+        self.data::<Data>().weight_policy = weight_policy;
+        Ok(())
+    }
 
+    default fn get_weight_policy(&self) -> WeightPolicy {
+        self.data::<Data>().weight_policy
+    }
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_MANAGER))]
+    default fn set_value_thresholds(&mut self, min_value: Option<Balance>, max_value: Option<Balance>) -> Result<(), ParticipantManagerError> {
+        if let (Some(min_value), Some(max_value)) = (min_value, max_value) {
+            if min_value > max_value {
+                return Err(ParticipantManagerError::InvalidThresholds);
+            }
+        }
+        self.data::<Data>().min_value = min_value;
+        self.data::<Data>().max_value = max_value;
+        Ok(())
+    }
+
+    default fn get_value_thresholds(&self) -> (Option<Balance>, Option<Balance>) {
+        (self.data::<Data>().min_value, self.data::<Data>().max_value)
+    }
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_MANAGER))]
+    default fn set_threshold_mode(&mut self, threshold_mode: ThresholdMode) -> Result<(), ParticipantManagerError> {
+        self.data::<Data>().threshold_mode = threshold_mode;
+        Ok(())
+    }
+
+    default fn get_threshold_mode(&self) -> ThresholdMode {
+        self.data::<Data>().threshold_mode
+    }
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_MANAGER))]
+    default fn update_participant(&mut self, pool_id: u32, account: AccountId, new_value: Balance) -> Result<(), ParticipantManagerError> {
+
+        if !self.is_pool_open(pool_id) {
+            return Err(ParticipantManagerError::PoolNotFound);
+        }
+
+        let era = self.get_current_era(pool_id);
+        if is_era_locked(self.data::<Data>(), pool_id, era) {
+            return Err(ParticipantManagerError::EraLocked);
+        }
+        let mut positions = self.data::<Data>().accounts_index.get(&(pool_id, era, account)).ok_or(ParticipantManagerError::ParticipantNotFound)?;
+
+        // the new value replaces the sum of the values given for this account
+        if !is_in_thresholds(self.data::<Data>(), new_value) {
+            return Err(ParticipantManagerError::ValueOutOfRange);
+        }
+        let new_raw_value = new_value;
+        let new_value = apply_weight_policy(&self.data::<Data>().weight_policy, new_raw_value);
+
+        // keep only the first entry of this participant, the last entries are removed first so the first one does not move
+        let mut old_value: Balance = 0;
+        while positions.len() > 1 {
+            let position = positions.pop().ok_or(ParticipantManagerError::ParticipantNotFound)?;
+            old_value += remove_entry(self.data::<Data>(), pool_id, era, position)?.value;
+        }
+        let position = positions[0];
+        let (page, index) = get_page_and_index(position, self.data::<Data>().page_size);
+
+        let mut participants = self.data::<Data>().participants.get(&(pool_id, era, page)).unwrap_or_default();
+        let participant = participants.get_mut(index).ok_or(ParticipantManagerError::ParticipantNotFound)?;
+        let first_value = participant.value;
+        old_value += first_value;
+        participant.value = new_value;
+        self.data::<Data>().participants.insert(&(pool_id, era, page), &participants);
+
+        // update the total and the index
+        let total_value = (self.get_total_value_for_era(pool_id, era) - first_value)
+            .checked_add(new_value).ok_or(ParticipantManagerError::AddOverFlow)?;
+        self.data::<Data>().total_value.insert(&(pool_id, era), &total_value);
+        let max_participants = self.data::<Data>().max_participants;
+        update_weight(&mut self.data::<Data>().weights_index, pool_id, era, max_participants, position, first_value, new_value);
+        self.data::<Data>().account_values.insert(&(pool_id, era, account), &new_value);
+        self.data::<Data>().raw_values.insert(&(pool_id, era, account), &new_raw_value);
+
+        self._emit_participant_updated_event(pool_id, era, account, old_value, new_value);
+        Ok(())
+    }
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_MANAGER))]
+    default fn remove_participant(&mut self, pool_id: u32, account: AccountId) -> Result<(), ParticipantManagerError> {
+
+        let era = self.get_current_era(pool_id);
+        if is_era_locked(self.data::<Data>(), pool_id, era) {
+            return Err(ParticipantManagerError::EraLocked);
+        }
+        let mut positions = self.data::<Data>().accounts_index.get(&(pool_id, era, account)).ok_or(ParticipantManagerError::ParticipantNotFound)?;
+
+        // remove all entries of this participant, starting with the last one
+        let mut removed_value: Balance = 0;
+        while let Some(position) = positions.pop() {
+            removed_value += remove_entry(self.data::<Data>(), pool_id, era, position)?.value;
+        }
+
+        self._emit_participant_removed_event(pool_id, era, account, removed_value);
+        Ok(())
+    }
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_MANAGER))]
+    default fn clear_data(&mut self, pool_id: u32) -> Result<(), ParticipantManagerError> {
+        let era = self.get_current_era(pool_id);
+        if is_era_locked(self.data::<Data>(), pool_id, era) {
+            return Err(ParticipantManagerError::EraLocked);
+        }
+        let mut nb_pages = u32::MAX;
+        clear_era(self.data::<Data>(), pool_id, era, &mut nb_pages);
+        self._emit_data_cleared_event(pool_id, era);
+        Ok(())
+    }
+
+    #[openbrush::modifiers(access_control::only_role(PARTICIPANT_MANAGER))]
+    default fn prune_eras(&mut self, pool_id: u32, before_era: u32) -> Result<(), ParticipantManagerError> {
+        // only the eras before the last locked era and before the current era can be pruned:
+        // the next ones are pending, not yet drawn or still receiving participants
+        let locked_era = self.get_locked_era(pool_id).unwrap_or_default();
+        if before_era > locked_era.min(self.get_current_era(pool_id)) {
+            return Err(ParticipantManagerError::EraNotPrunable);
+        }
+        let eras: Vec<u32> = self.get_eras(pool_id).into_iter()
+            .filter(|era| *era < before_era)
+            .collect();
+        // the number of removed pages is bounded, the next ones are removed by the next calls
+        let mut nb_pages = MAX_PRUNED_PAGES;
+        for era in eras {
+            if !clear_era(self.data::<Data>(), pool_id, era, &mut nb_pages) {
+                break;
+            }
+            self._emit_data_cleared_event(pool_id, era);
+        }
         Ok(())
     }
 
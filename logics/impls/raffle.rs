@@ -1,6 +1,7 @@
 use ink::prelude::vec::Vec;
 use openbrush::contracts::access_control::{access_control, RoleType};
-use openbrush::traits::{AccountId, Balance, Storage};
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, Hash, Storage};
 
 pub use crate::traits::raffle::{
     *,
@@ -11,16 +12,47 @@ pub use crate::traits::random::{Random, RandomError};
 
 
 use crate::impls::participant_manager::ParticipantManager;
+use crate::impls::random_generator::RandomSequence;
 
 pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
 pub const RAFFLE_MANAGER: RoleType = ink::selector_id!("RAFFLE_MANAGER");
+/// time (in milliseconds) after which a raffle still waiting for the random seed can be cancelled
+pub const PENDING_RAFFLE_TIMEOUT: u64 = 24 * 60 * 60 * 1000;
 
 #[derive(Default, Debug)]
 #[openbrush::upgradeable_storage(STORAGE_KEY)]
 pub struct Data {
-    ratio_distribution: Vec<Balance>,
-    total_ratio_distribution: Balance,
-    last_era_done: u32,
+    /// ratio distribution by pool
+    ratio_distribution: Mapping<u32, Vec<Balance>>,
+    /// total ratio by pool
+    total_ratio_distribution: Mapping<u32, Balance>,
+    /// last era done by pool
+    last_era_done: Mapping<u32, u32>,
+    /// raffle waiting for the random seed by pool
+    pending_raffle: Mapping<u32, PendingRaffle>,
+    /// if true, an account can win only one prize by raffle, by pool
+    distinct_winners: Mapping<u32, bool>,
+}
+
+/// Scale the random value (drawn on the full range of u128) to a value between 1 and max (both included).
+/// The high bits of the wide multiplication random * max are used.
+fn scale_random_value(random: u128, max: u128) -> u128 {
+    const MASK: u128 = u64::MAX as u128;
+    let (random_high, random_low) = (random >> 64, random & MASK);
+    let (max_high, max_low) = (max >> 64, max & MASK);
+    let low_low = random_low * max_low;
+    let high_low = random_high * max_low;
+    let low_high = random_low * max_high;
+    let high_high = random_high * max_high;
+    let middle = (low_low >> 64) + (high_low & MASK) + (low_high & MASK);
+    high_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64) + 1
+}
+
+/// compute the reward for a winner based on the ratio
+fn compute_reward(total_rewards: Balance, ratio: Balance, total_ratio: Balance) -> Result<Balance, RaffleError> {
+    total_rewards
+        .checked_mul(ratio).ok_or(MulOverFlow)?
+        .checked_div(total_ratio).ok_or(DivByZero)
 }
 
 impl<T> Raffle for T
@@ -32,7 +64,7 @@ where
 {
 
     #[openbrush::modifiers(access_control::only_role(RAFFLE_MANAGER))]
-    default fn set_ratio_distribution(&mut self, ratio: Vec<Balance>, total_ratio: Balance) -> Result<(), RaffleError> {
+    default fn set_ratio_distribution(&mut self, pool_id: u32, ratio: Vec<Balance>, total_ratio: Balance) -> Result<(), RaffleError> {
 
         let mut total = 0;
         for r in &ratio {
@@ -42,39 +74,63 @@ where
             return Err(IncorrectRatio);
         }
 
-        self.data::<Data>().ratio_distribution = ratio;
-        self.data::<Data>().total_ratio_distribution = total_ratio;
+        self.data::<Data>().ratio_distribution.insert(&pool_id, &ratio);
+        self.data::<Data>().total_ratio_distribution.insert(&pool_id, &total_ratio);
         Ok(())
     }
 
-    default fn get_ratio_distribution(&self) -> Vec<Balance> {
-        let ratio = &self.data::<Data>().ratio_distribution;
-        ratio.to_vec()
+    default fn get_ratio_distribution(&self, pool_id: u32) -> Vec<Balance> {
+        self.data::<Data>().ratio_distribution.get(&pool_id).unwrap_or_default()
     }
 
-    default fn get_total_ratio_distribution(&self) -> Balance {
-        self.data::<Data>().total_ratio_distribution
+    default fn get_total_ratio_distribution(&self, pool_id: u32) -> Balance {
+        self.data::<Data>().total_ratio_distribution.get(&pool_id).unwrap_or_default()
     }
 
-    default fn get_last_era_done(&self) -> u32 {
-        self.data::<Data>().last_era_done
+    default fn get_last_era_done(&self, pool_id: u32) -> u32 {
+        self.data::<Data>().last_era_done.get(&pool_id).unwrap_or_default()
     }
 
     #[openbrush::modifiers(access_control::only_role(RAFFLE_MANAGER))]
-    default fn _run_raffle(
-        &mut self,
-        era: u32,
-        total_rewards: Balance
-    ) -> Result<Vec<(AccountId, Balance)>, RaffleError> {
+    default fn set_distinct_winners(&mut self, pool_id: u32, distinct_winners: bool) -> Result<(), RaffleError> {
+        self.data::<Data>().distinct_winners.insert(&pool_id, &distinct_winners);
+        Ok(())
+    }
+
+    default fn is_distinct_winners(&self, pool_id: u32) -> bool {
+        self.data::<Data>().distinct_winners.get(&pool_id).unwrap_or_default()
+    }
+
+    default fn get_pending_raffle(&self, pool_id: u32) -> Option<PendingRaffle> {
+        self.data::<Data>().pending_raffle.get(&pool_id)
+    }
+
+    default fn _get_raffle_settings(&self, pool_id: u32) -> RaffleSettings {
+        RaffleSettings {
+            ratio_distribution: self.get_ratio_distribution(pool_id),
+            total_ratio_distribution: self.get_total_ratio_distribution(pool_id),
+            distinct_winners: self.is_distinct_winners(pool_id),
+        }
+    }
+
+    default fn _check_raffle_settings(&self, pool_id: u32, era: u32, total_rewards: Balance) -> Result<(), RaffleError> {
+
+        // check if the pool is open
+        if !self.is_pool_open(pool_id) {
+            return Err(PoolNotFound);
+        }
 
         // check if the raffle has not been done
-        if self.get_last_era_done() >= era {
+        if self.get_last_era_done(pool_id) >= era {
             return Err(RaffleAlreadyDone);
         }
 
-        let nb_winners = self.data::<Data>().ratio_distribution.len();
+        // check if another raffle is not waiting for the random seed
+        if self.data::<Data>().pending_raffle.contains(&pool_id) {
+            return Err(RaffleAlreadyPending);
+        }
 
-        if nb_winners == 0 {
+        if self.get_ratio_distribution(pool_id).is_empty() {
             // no ration set
             return Err(NoRatioSet);
         }
@@ -84,43 +140,275 @@ where
             return Err(NoReward);
         }
 
-        if self.get_nb_participants() == 0 {
+        Ok(())
+    }
+
+    default fn _check_raffle(&self, pool_id: u32, era: u32, total_rewards: Balance) -> Result<(), RaffleError> {
+
+        self._check_raffle_settings(pool_id, era, total_rewards)?;
+
+        let nb_participants = self.get_nb_participants_for_era(pool_id, era);
+        if nb_participants == 0 {
             // no participant
             return Err(NoParticipant);
         }
 
-        // total value locked by all participants
-        let total_value = self.get_total_value();
-        // initialize the empty list of randomly selected values
+        if self.is_distinct_winners(pool_id)
+            && (self.get_nb_accounts_for_era(pool_id, era) as usize) < self.get_ratio_distribution(pool_id).len() {
+            // not enough distinct accounts to have distinct winners
+            return Err(NotEnoughParticipants);
+        }
+
+        Ok(())
+    }
+
+    default fn _get_random_range(&self, pool_id: u32, era: u32, settings: &RaffleSettings) -> (u128, u128) {
+        if settings.distinct_winners {
+            // the weight of the previous winners is removed after each draw,
+            // the values are drawn on the full range and scaled to the remaining weight
+            (0, u128::MAX)
+        } else {
+            // each unit of weight has the same chance to be selected
+            (1, self.get_total_value_for_era(pool_id, era))
+        }
+    }
+
+    default fn _select_winners(
+        &self,
+        pool_id: u32,
+        era: u32,
+        total_rewards: Balance,
+        settings: &RaffleSettings,
+        random_values: Vec<u128>
+    ) -> Result<Vec<(AccountId, Balance)>, RaffleError> {
+
+        let ratio_distribution = &settings.ratio_distribution;
+        let total_ratio_distribution = settings.total_ratio_distribution;
+        let nb_winners = ratio_distribution.len();
+        let distinct_winners = settings.distinct_winners;
+        // initialize the empty list of winners
         let mut winner_and_reward = Vec::with_capacity(nb_winners);
+        // accounts already selected and their weight not yet removed from the raffle
+        let mut selected_accounts = Vec::with_capacity(nb_winners);
+        let mut remaining_value = self.get_total_value_for_era(pool_id, era);
 
-        for i in 0..nb_winners {
-            // generate the random value
-            let random_value = self.get_random_number(0, total_value)?;
-            // select the participant matching with this value
-            let winner = self.get_participant(random_value).ok_or(NoSelectedParticipant)?;
+        for (i, random_value) in random_values.into_iter().enumerate() {
+
+            let winner = if distinct_winners {
+                if remaining_value == 0 {
+                    // all eligible participants have already been selected
+                    return Err(NotEnoughParticipants);
+                }
+                // select the participant matching with this value, without the previous winners
+                let value = scale_random_value(random_value, remaining_value);
+                let (winner, winner_value) = self._get_participant_excluding(pool_id, era, value, &selected_accounts)
+                    .ok_or(NoSelectedParticipant)?;
+                // remove the weight of this winner for the next draws
+                remaining_value = remaining_value.checked_sub(winner_value).ok_or(SubOverFlow)?;
+                winner
+            } else {
+                // select the participant matching with this value
+                self.get_participant_for_era(pool_id, era, random_value).ok_or(NoSelectedParticipant)?
+            };
+            selected_accounts.push(winner);
 
             // select the erwards ratio
-            let ratio = self.data::<Data>().ratio_distribution.get(i).unwrap_or(&0);
+            let ratio = ratio_distribution.get(i).unwrap_or(&0);
             if *ratio != 0 {
                 // compute the reward for this winner based on the ratio
-                let amount = total_rewards
-                    .checked_mul(*ratio).ok_or(MulOverFlow)?
-                    .checked_div(self.data::<Data>().total_ratio_distribution).ok_or(DivByZero)?;
+                let amount = compute_reward(total_rewards, *ratio, total_ratio_distribution)?;
                 // add the pending rewards for this account
                 winner_and_reward.push((winner, amount));
             }
         }
 
-        // set the raffle is done
-        self.data::<Data>().last_era_done = era;
+        Ok(winner_and_reward)
+    }
+
+    default fn _draw_random_values(
+        &mut self,
+        era: u32,
+        secret: Option<[u8; 32]>,
+        count: u32,
+        min: u128,
+        max: u128
+    ) -> Result<Vec<u128>, RaffleError> {
+        let random_values = match secret {
+            // the secret committed for this era is revealed with the draw
+            Some(secret) => self.get_random_numbers_with_secret(era, secret, count, min, max)?,
+            None => self.get_random_numbers(count, min, max)?,
+        };
+        Ok(random_values)
+    }
+
+    #[openbrush::modifiers(access_control::only_role(RAFFLE_MANAGER))]
+    default fn _run_raffle(
+        &mut self,
+        pool_id: u32,
+        era: u32,
+        total_rewards: Balance,
+        secret: Option<[u8; 32]>
+    ) -> Result<Vec<(AccountId, Balance)>, RaffleError> {
+
+        self._check_raffle(pool_id, era, total_rewards)?;
+
+        let settings = self._get_raffle_settings(pool_id);
+        let nb_winners = settings.ratio_distribution.len();
+        let (min, max) = self._get_random_range(pool_id, era, &settings);
+
+        // generate the random values in one call
+        let random_values = self._draw_random_values(era, secret, nb_winners as u32, min, max)?;
+
+        let winner_and_reward = self._select_winners(pool_id, era, total_rewards, &settings, random_values)?;
+
+        // set the raffle is done, the participants of this era can't be changed anymore
+        self.data::<Data>().last_era_done.insert(&pool_id, &era);
+        self._lock_era(pool_id, era);
 
         Ok(winner_and_reward)
     
     }
 
+    #[openbrush::modifiers(access_control::only_role(RAFFLE_MANAGER))]
+    default fn _draw_winning_offsets(
+        &mut self,
+        pool_id: u32,
+        era: u32,
+        total_value: Balance,
+        total_rewards: Balance,
+        secret: Option<[u8; 32]>
+    ) -> Result<Vec<(Balance, Balance)>, RaffleError> {
 
+        self._check_raffle_settings(pool_id, era, total_rewards)?;
 
-}
+        if total_value == 0 {
+            // no participant
+            return Err(NoParticipant);
+        }
+
+        // each unit of weight has the same chance to be selected
+        let ratio_distribution = self.get_ratio_distribution(pool_id);
+        let total_ratio_distribution = self.get_total_ratio_distribution(pool_id);
+        let nb_winners = ratio_distribution.len();
+        let random_values = self._draw_random_values(era, secret, nb_winners as u32, 1, total_value)?;
+
+        let mut offset_and_reward = Vec::with_capacity(nb_winners);
+        for (i, random_value) in random_values.into_iter().enumerate() {
+            let ratio = ratio_distribution.get(i).unwrap_or(&0);
+            if *ratio != 0 {
+                let amount = compute_reward(total_rewards, *ratio, total_ratio_distribution)?;
+                offset_and_reward.push((random_value, amount));
+            }
+        }
+
+        // set the raffle is done, the participants of this era can't be changed anymore
+        self.data::<Data>().last_era_done.insert(&pool_id, &era);
+        self._lock_era(pool_id, era);
+
+        Ok(offset_and_reward)
+    }
+
+    #[openbrush::modifiers(access_control::only_role(RAFFLE_MANAGER))]
+    default fn _start_raffle(
+        &mut self,
+        pool_id: u32,
+        era: u32,
+        total_rewards: Balance,
+        secret: Option<[u8; 32]>
+    ) -> Result<u32, RaffleError> {
+
+        self._check_raffle(pool_id, era, total_rewards)?;
+
+        // request the random seed
+        let request_id = self.request_random_seed(era, secret)?;
+
+        // the participants and the settings can't be changed while the raffle is waiting for the seed
+        self._lock_era(pool_id, era);
+        let pending_raffle = PendingRaffle {
+            era,
+            total_rewards,
+            request_id,
+            nb_participants: self.get_nb_participants_for_era(pool_id, era),
+            total_value: self.get_total_value_for_era(pool_id, era),
+            settings: self._get_raffle_settings(pool_id),
+            started_at: Self::env().block_timestamp(),
+        };
+        self.data::<Data>().pending_raffle.insert(&pool_id, &pending_raffle);
+
+        Ok(request_id)
+    }
+
+    #[openbrush::modifiers(access_control::only_role(RAFFLE_MANAGER))]
+    default fn _finish_raffle(&mut self, pool_id: u32) -> Result<(PendingRaffle, Vec<(AccountId, Balance)>), RaffleError> {
+
+        let pending_raffle = self.get_pending_raffle(pool_id).ok_or(NoPendingRaffle)?;
+
+        // check the pool has not been closed while the raffle was waiting for the seed
+        if !self.is_pool_open(pool_id) {
+            return Err(PoolNotFound);
+        }
+
+        // check the participants are the same as when the raffle has been started
+        if self.get_nb_participants_for_era(pool_id, pending_raffle.era) != pending_raffle.nb_participants
+            || self.get_total_value_for_era(pool_id, pending_raffle.era) != pending_raffle.total_value {
+            return Err(ParticipantsChanged);
+        }
+
+        // get the random seed provided for this request
+        let seed = self.get_random_seed(pending_raffle.request_id)?;
+
+        // the settings saved when the raffle has been started are used
+        let settings = &pending_raffle.settings;
+        let nb_winners = settings.ratio_distribution.len();
+        let (min, max) = self._get_random_range(pool_id, pending_raffle.era, settings);
+
+        // derive the random values from the seed
+        let mut random_sequence = RandomSequence::new(&seed);
+        let mut random_values = Vec::with_capacity(nb_winners);
+        for _ in 0..nb_winners {
+            let random_value = random_sequence.next_number(min, max).map_err(RandomError::from)?;
+            random_values.push(random_value);
+        }
+
+        let winner_and_reward = self._select_winners(pool_id, pending_raffle.era, pending_raffle.total_rewards, settings, random_values)?;
+
+        // set the raffle is done
+        self.data::<Data>().last_era_done.insert(&pool_id, &pending_raffle.era);
+        self.data::<Data>().pending_raffle.remove(&pool_id);
+
+        Ok((pending_raffle, winner_and_reward))
+    }
+
+    #[openbrush::modifiers(access_control::only_role(RAFFLE_MANAGER))]
+    default fn _commit_secret(&mut self, era: u32, commitment: Hash) -> Result<(), RaffleError> {
+        // the commitments are by era, no pool can have participants in this era
+        for pool_id in self.get_pools() {
+            if self.get_nb_participants_for_era(pool_id, era) > 0 {
+                return Err(ParticipantsAlreadyAdded);
+            }
+        }
+        self.commit_secret(era, commitment)?;
+        Ok(())
+    }
+
+    #[openbrush::modifiers(access_control::only_role(RAFFLE_MANAGER))]
+    default fn _cancel_raffle(&mut self, pool_id: u32) -> Result<PendingRaffle, RaffleError> {
+
+        let pending_raffle = self.get_pending_raffle(pool_id).ok_or(NoPendingRaffle)?;
 
+        // the oracle has some time to provide the seed
+        if Self::env().block_timestamp() < pending_raffle.started_at.saturating_add(PENDING_RAFFLE_TIMEOUT) {
+            return Err(PendingRaffleNotExpired);
+        }
+
+        // the raffle can't be cancelled once the seed is known, it must be finished
+        if self.get_random_seed(pending_raffle.request_id).is_ok() {
+            return Err(SeedAlreadyProvided);
+        }
+
+        self.data::<Data>().pending_raffle.remove(&pool_id);
 
+        Ok(pending_raffle)
+    }
+
+}
@@ -1,25 +1,154 @@
 use ink::env::hash::{Keccak256, HashOutput};
 use ink::prelude::vec::Vec;
-use openbrush::traits::Storage;
+use openbrush::storage::Mapping;
+use openbrush::traits::{Hash, Storage};
 use openbrush::contracts::access_control::{access_control, RoleType};
+use scale::Encode;
 
-pub use crate::traits::random_generator::{
-    *,
-    RandomGeneratorError::*,
+pub use crate::traits::{
+    random_generator,
+    random_generator::*,
+    random_generator::RandomGeneratorError::*,
 };
 
 pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
 pub const RANDOM_GENERATOR_CONSUMER: RoleType = ink::selector_id!("RANDOM_GENERATOR_CONSUMER");
 pub const RANDOM_GENERATOR_MANAGER: RoleType = ink::selector_id!("RANDOM_GENERATOR_MANAGER");
+pub const RANDOM_GENERATOR_ORACLE: RoleType = ink::selector_id!("RANDOM_GENERATOR_ORACLE");
 
 #[derive(Default, Debug)]
 #[openbrush::upgradeable_storage(STORAGE_KEY)]
 pub struct Data {
     salt: u64,
+    /// commitments (hash of the secret) by era
+    commitments: Mapping<u32, Hash>,
+    /// revealed secrets by era
+    secrets: Mapping<u32, [u8; 32]>,
+    next_request_id: u32,
+    /// era by request id
+    requests: Mapping<u32, u32>,
+    /// seed built on chain when the request is sent, this is the input signed by the oracle, by request id
+    request_seeds: Mapping<u32, [u8; 32]>,
+    /// last randomness provided by the oracle, mixed in the seed of the next requests
+    last_randomness: [u8; 32],
+    /// random seeds provided by the oracle by request id
+    randomness: Mapping<u32, [u8; 32]>,
+    /// public key used to verify the seeds provided by the oracle
+    oracle_public_key: Option<[u8; 33]>,
+}
+
+/// Number of bytes of the hash output used to build a random word
+const WORD_SIZE: usize = 16;
+
+/// Convert the output of the hash into a random number between min and max (both included).
+/// The full output is used: it is split in 128-bit words and the words falling in the biased zone
+/// are rejected (rejection sampling) so that every value in the range has the same chance.
+/// If all words are rejected, the output is hashed again.
+pub fn get_uniform_random_number(
+    mut output: [u8; 32],
+    min: u128,
+    max: u128
+) -> Result<u128, RandomGeneratorError> {
+
+    let range = max.checked_sub(min).ok_or(SubOverFlow)?;
+    let nb_values = match range.checked_add(1) {
+        Some(nb_values) => nb_values,
+        // the range covers all u128 values, there is no bias
+        None => return Ok(to_random_word(&output[..WORD_SIZE])),
+    };
+    // 2^128 mod nb_values: the words lower than this threshold are rejected
+    let threshold = nb_values.wrapping_neg() % nb_values;
+
+    loop {
+        for word in output.chunks_exact(WORD_SIZE) {
+            let random_word = to_random_word(word);
+            if random_word >= threshold {
+                let random = random_word.checked_rem(nb_values).ok_or(DivByZero)?;
+                return random.checked_add(min).ok_or(AddOverFlow);
+            }
+        }
+        // all words have been rejected, hash again the output
+        let mut next_output = <Keccak256 as HashOutput>::Type::default();
+        ink::env::hash_bytes::<Keccak256>(&output, &mut next_output);
+        output = next_output;
+    }
+}
+
+/// Sequence of random numbers derived from a single seed by hash chaining
+pub struct RandomSequence {
+    output: [u8; 32],
+}
+
+impl RandomSequence {
+
+    pub fn new(seed: &[u8]) -> Self {
+        let mut output = <Keccak256 as HashOutput>::Type::default();
+        ink::env::hash_bytes::<Keccak256>(seed, &mut output);
+        RandomSequence { output }
+    }
+
+    /// return the next random number between min and max (both included)
+    pub fn next_number(&mut self, min: u128, max: u128) -> Result<u128, RandomGeneratorError> {
+        let random = get_uniform_random_number(self.output, min, max)?;
+        // chain the hash for the next number
+        let mut next_output = <Keccak256 as HashOutput>::Type::default();
+        ink::env::hash_bytes::<Keccak256>(&self.output, &mut next_output);
+        self.output = next_output;
+        Ok(random)
+    }
+}
+
+/// Return the hash of the message (era, request_id, seed) signed by the oracle
+pub fn get_randomness_message(era: u32, request_id: u32, seed: [u8; 32]) -> [u8; 32] {
+    let mut output = <Keccak256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Keccak256>(&(era, request_id, seed).encode(), &mut output);
+    output
+}
+
+/// Order of the secp256k1 curve divided by 2: a canonical signature has a s value lower or equal than this value
+const SECP256K1_HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+/// return true if the signature (r, s, v) has a low s value.
+/// For each signature (r, s), (r, n - s) is also valid: only the low s value is accepted to make the proof unique
+fn is_low_s(signature: &[u8; 65]) -> bool {
+    // big endian bytes: the lexicographic order is the numeric order
+    signature[32..64] <= SECP256K1_HALF_ORDER[..]
+}
+
+/// build the seed from the timestamp, the salt and the secret revealed for the era (if any)
+fn get_seed_input(timestamp: u64, salt: u64, secret: Option<&[u8; 32]>) -> Vec<u8> {
+    let mut input: Vec<u8> = Vec::new();
+    input.extend_from_slice(&timestamp.to_be_bytes());
+    input.extend_from_slice(&salt.to_be_bytes());
+    if let Some(secret) = secret {
+        input.extend_from_slice(secret);
+    }
+    input
+}
+
+/// generate count random numbers between min and max from a single seed
+fn get_random_sequence(input: &[u8], count: u32, min: u128, max: u128) -> Result<Vec<u128>, RandomGeneratorError> {
+    // all numbers are derived from the same seed by hash chaining
+    let mut random_sequence = RandomSequence::new(input);
+    let mut randoms = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        randoms.push(random_sequence.next_number(min, max)?);
+    }
+    Ok(randoms)
+}
+
+fn to_random_word(bytes: &[u8]) -> u128 {
+    let mut word = [0u8; WORD_SIZE];
+    word.copy_from_slice(bytes);
+    u128::from_be_bytes(word)
 }
 
 impl<T> RandomGenerator for T
     where
+        T: random_generator::Internal,
         T: Storage<Data>,
         T: Storage<access_control::Data>,
 {
@@ -28,27 +157,42 @@ impl<T> RandomGenerator for T
     default fn get_random_number(&mut self, min: u128, max: u128) -> Result<u128, RandomGeneratorError> {
         let seed = Self::env().block_timestamp();
         let salt = self.data::<Data>().salt;
-        let mut input: Vec<u8> = Vec::new();
-        input.extend_from_slice(&seed.to_be_bytes());
-        input.extend_from_slice(&salt.to_be_bytes());
-        let mut output = <Keccak256 as HashOutput>::Type::default();
-        ink::env::hash_bytes::<Keccak256>(&input, &mut output);
+        let input = get_seed_input(seed, salt, None);
         self.data::<Data>().salt = salt + 1;
 
+        let random = RandomSequence::new(&input).next_number(min, max)?;
 
-        let a = output[0] as u128;
+        ink::env::debug_println!("random {}", random);
 
-        //(a  as u32) * (max - min) / (u32::MAX) + min
-        let b = max.checked_sub(min).ok_or(SubOverFlow)?;
-        let c = a.checked_mul(b).ok_or(MulOverFlow)?;
-        let d = c.checked_div(u8::MAX as u128).ok_or(DivByZero)?;
-        let e = d.checked_add(min).ok_or(AddOverFlow)?;
+        Ok(random)
+    }
 
-        ink::env::debug_println!("random {}", e);
+    #[openbrush::modifiers(access_control::only_role(RANDOM_GENERATOR_CONSUMER))]
+    default fn get_random_numbers(&mut self, count: u32, min: u128, max: u128) -> Result<Vec<u128>, RandomGeneratorError> {
+        let seed = Self::env().block_timestamp();
+        let salt = self.data::<Data>().salt;
+        let input = get_seed_input(seed, salt, None);
+        self.data::<Data>().salt = salt + 1;
 
-        Ok(e)
+        let randoms = get_random_sequence(&input, count, min, max)?;
+
+        Ok(randoms)
     }
 
+    #[openbrush::modifiers(access_control::only_role(RANDOM_GENERATOR_CONSUMER))]
+    default fn get_random_numbers_with_secret(&mut self, era: u32, secret: [u8; 32], count: u32, min: u128, max: u128) -> Result<Vec<u128>, RandomGeneratorError> {
+        // the secret is revealed in the same call as the draw
+        self._reveal_secret(era, secret)?;
+
+        let seed = Self::env().block_timestamp();
+        let salt = self.data::<Data>().salt;
+        let input = get_seed_input(seed, salt, Some(&secret));
+        self.data::<Data>().salt = salt + 1;
+
+        let randoms = get_random_sequence(&input, count, min, max)?;
+
+        Ok(randoms)
+    }
 
     #[openbrush::modifiers(access_control::only_role(RANDOM_GENERATOR_MANAGER))]
     default fn get_salt(&mut self) -> Result<u64, RandomGeneratorError>{
@@ -56,10 +200,121 @@ impl<T> RandomGenerator for T
     }
 
     #[openbrush::modifiers(access_control::only_role(RANDOM_GENERATOR_MANAGER))]
-    default fn set_salt(&mut self, salt: u64) -> Result<(), RandomGeneratorError>{
-        self.data::<Data>().salt = salt;
+    default fn commit_secret(&mut self, era: u32, commitment: Hash) -> Result<(), RandomGeneratorError>{
+        if self.data::<Data>().commitments.contains(&era) {
+            return Err(CommitmentAlreadyExists);
+        }
+        self.data::<Data>().commitments.insert(&era, &commitment);
+        Ok(())
+    }
+
+    default fn _reveal_secret(&mut self, era: u32, secret: [u8; 32]) -> Result<(), RandomGeneratorError>{
+        let commitment = self.data::<Data>().commitments.get(&era).ok_or(MissingCommitment)?;
+        if self.data::<Data>().secrets.contains(&era) {
+            return Err(SecretAlreadyRevealed);
+        }
+
+        // check the secret matches with the commitment
+        let mut output = <Keccak256 as HashOutput>::Type::default();
+        ink::env::hash_bytes::<Keccak256>(&secret, &mut output);
+        if Hash::from(output) != commitment {
+            return Err(InvalidSecret);
+        }
+
+        self.data::<Data>().secrets.insert(&era, &secret);
+        Ok(())
+    }
+
+    default fn get_commitment(&self, era: u32) -> Option<Hash>{
+        self.data::<Data>().commitments.get(&era)
+    }
+
+    default fn is_secret_revealed(&self, era: u32) -> bool{
+        self.data::<Data>().secrets.contains(&era)
+    }
+
+    #[openbrush::modifiers(access_control::only_role(RANDOM_GENERATOR_CONSUMER))]
+    default fn request_randomness(&mut self, era: u32, secret: Option<[u8; 32]>) -> Result<u32, RandomGeneratorError>{
+        if let Some(secret) = secret {
+            // the secret is revealed with the request, before the seed is known
+            self._reveal_secret(era, secret)?;
+        }
+        let request_id = self.data::<Data>().next_request_id;
+        self.data::<Data>().next_request_id = request_id.checked_add(1).ok_or(AddOverFlow)?;
+        self.data::<Data>().requests.insert(&request_id, &era);
+
+        // the input signed by the oracle is built on chain, it can't be chosen by the oracle
+        let input = (
+            self.data::<Data>().last_randomness,
+            Self::env().block_number(),
+            Self::env().block_timestamp(),
+            era,
+            request_id,
+        ).encode();
+        let mut request_seed = <Keccak256 as HashOutput>::Type::default();
+        ink::env::hash_bytes::<Keccak256>(&input, &mut request_seed);
+        self.data::<Data>().request_seeds.insert(&request_id, &request_seed);
+
+        Ok(request_id)
+    }
+
+    #[openbrush::modifiers(access_control::only_role(RANDOM_GENERATOR_ORACLE))]
+    default fn fulfill_randomness(&mut self, request_id: u32, proof: Vec<u8>) -> Result<(), RandomGeneratorError>{
+        let era = self.data::<Data>().requests.get(&request_id).ok_or(RequestNotFound)?;
+        let seed = self.data::<Data>().request_seeds.get(&request_id).ok_or(RequestNotFound)?;
+        if self.data::<Data>().randomness.contains(&request_id) {
+            return Err(RequestAlreadyFulfilled);
+        }
+        let oracle_public_key = self.data::<Data>().oracle_public_key.ok_or(MissingOraclePublicKey)?;
+
+        // check the seed built on chain has been signed by the oracle with a canonical signature
+        let signature: [u8; 65] = proof.try_into().map_err(|_| InvalidProof)?;
+        if !is_low_s(&signature) {
+            return Err(InvalidProof);
+        }
+        let message = get_randomness_message(era, request_id, seed);
+        let mut public_key = [0u8; 33];
+        ink::env::ecdsa_recover(&signature, &message, &mut public_key).map_err(|_| InvalidProof)?;
+        if public_key != oracle_public_key {
+            return Err(InvalidProof);
+        }
+
+        // the verified signature is the source of entropy, mixed with the secret revealed for the era
+        let mut input: Vec<u8> = signature.to_vec();
+        if let Some(secret) = self.data::<Data>().secrets.get(&era) {
+            input.extend_from_slice(&secret);
+        }
+        let mut randomness = <Keccak256 as HashOutput>::Type::default();
+        ink::env::hash_bytes::<Keccak256>(&input, &mut randomness);
+        self.data::<Data>().randomness.insert(&request_id, &randomness);
+        self.data::<Data>().last_randomness = randomness;
+
+        self._emit_randomness_verified_event(request_id, era, randomness);
+        Ok(())
+    }
+
+    default fn get_randomness(&self, request_id: u32) -> Option<[u8; 32]>{
+        self.data::<Data>().randomness.get(&request_id)
+    }
+
+    default fn get_request_era(&self, request_id: u32) -> Option<u32>{
+        self.data::<Data>().requests.get(&request_id)
+    }
+
+    default fn get_request_seed(&self, request_id: u32) -> Option<[u8; 32]>{
+        self.data::<Data>().request_seeds.get(&request_id)
+    }
+
+    #[openbrush::modifiers(access_control::only_role(RANDOM_GENERATOR_MANAGER))]
+    default fn set_oracle_public_key(&mut self, public_key: [u8; 33]) -> Result<(), RandomGeneratorError>{
+        self.data::<Data>().oracle_public_key = Some(public_key);
+        self._emit_oracle_public_key_set_event(public_key);
         Ok(())
     }
 
+    default fn get_oracle_public_key(&self) -> Option<[u8; 33]>{
+        self.data::<Data>().oracle_public_key
+    }
+
 
 }
@@ -0,0 +1,238 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+#[openbrush::contract(env = lucky::traits::dapps_staking::CustomEnvironment)]
+pub mod dapps_staking {
+    use openbrush::contracts::access_control::{*, access_control};
+    use openbrush::traits::Storage;
+
+    use lucky::impls::{
+        *,
+        participant_manager::*,
+        dapps_staking::*,
+    };
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct Contract {
+        #[storage_field]
+        participant_manager: participant_manager::Data,
+        #[storage_field]
+        access: access_control::Data,
+        dapp_address: Option<AccountId>,
+    }
+
+    pub const POOL_ID: u32 = 1;
+
+    /// Errors occurred in the contract
+    #[derive(Debug, Eq, PartialEq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum ContractError {
+        ParticipantManagerError(ParticipantManagerError),
+        DappsStakingReaderError(DappsStakingReaderError),
+    }
+
+    /// convertor from ParticipantManagerError to ContractError
+    impl From<ParticipantManagerError> for ContractError {
+        fn from(error: ParticipantManagerError) -> Self {
+            ContractError::ParticipantManagerError(error)
+        }
+    }
+
+    /// convertor from DappsStakingReaderError to ContractError
+    impl From<DappsStakingReaderError> for ContractError {
+        fn from(error: DappsStakingReaderError) -> Self {
+            ContractError::DappsStakingReaderError(error)
+        }
+    }
+
+    impl ParticipantManager for Contract {}
+    impl AccessControl for Contract{}
+    impl DappsStakingReader for Contract{}
+
+    impl dapps_staking::Internal for Contract {
+        fn _read_current_era(&self) -> u32 {
+            self.env().extension().read_current_era()
+        }
+
+        fn _read_staked_amount_on_contract(&self, staker: AccountId, contract: AccountId) -> Balance {
+            self.env().extension().read_staked_amount_on_contract(staker, contract)
+        }
+    }
+
+    impl participant_manager::Internal for Contract {
+        fn _emit_participants_added_event(&self, _pool_id: u32, _era: u32, _nb_accepted: u32, _nb_rejected: u32, _added_value: Balance, _total_value: Balance){
+            // no event for the tests
+        }
+        fn _emit_participant_updated_event(&self, _pool_id: u32, _era: u32, _account: AccountId, _old_value: Balance, _new_value: Balance){
+            // no event for the tests
+        }
+        fn _emit_participant_removed_event(&self, _pool_id: u32, _era: u32, _account: AccountId, _value: Balance){
+            // no event for the tests
+        }
+        fn _emit_data_cleared_event(&self, _pool_id: u32, _era: u32){
+            // no event for the tests
+        }
+    }
+
+    impl Contract {
+        #[ink(constructor)]
+        pub fn new(dapp_address: AccountId) -> Self {
+            let mut instance = Self::default();
+            let caller = instance.env().caller();
+            instance._init_with_admin(caller);
+            instance._init_participant_manager(300, 1800).expect("Should init the participant manager");
+            instance.grant_role(PARTICIPANT_MANAGER, caller).expect("Should grant the role PARTICIPANT_MANAGER");
+            instance._create_pool(POOL_ID).expect("Should create the pool");
+            instance.dapp_address = Some(dapp_address);
+            instance
+        }
+
+        #[ink(message)]
+        pub fn add_participants_from_dapps_staking(&mut self, pool_id: u32, era: u32, stakers: Vec<AccountId>) -> Result<(), ContractError>{
+
+            let dapp_address = self.dapp_address.unwrap();
+            let participants = self._read_stakes(dapp_address, era, stakers)?;
+
+            self.add_participants(pool_id, era, participants)?;
+            Ok(())
+        }
+    }
+
+    mod tests {
+        use std::collections::HashMap;
+        use ink::env::test::ChainExtension;
+        use ink::env::debug_println;
+        use openbrush::test_utils::accounts;
+        use scale::{Decode, Encode};
+
+        use super::*;
+
+        /// mock of the function read_current_era of the dApps Staking chain extension
+        struct MockReadCurrentEra {
+            era: u32,
+        }
+
+        impl ChainExtension for MockReadCurrentEra {
+            fn func_id(&self) -> u32 {
+                1
+            }
+
+            fn call(&mut self, _input: &[u8], output: &mut Vec<u8>) -> u32 {
+                self.era.encode_to(output);
+                0
+            }
+        }
+
+        /// mock of the function read_staked_amount_on_contract of the dApps Staking chain extension
+        struct MockReadStakedAmountOnContract {
+            dapp: AccountId,
+            stakes: HashMap<AccountId, Balance>,
+        }
+
+        impl ChainExtension for MockReadStakedAmountOnContract {
+            fn func_id(&self) -> u32 {
+                6
+            }
+
+            fn call(&mut self, mut input: &[u8], output: &mut Vec<u8>) -> u32 {
+                let (staker, contract) = <(AccountId, AccountId)>::decode(&mut input).unwrap();
+                let staked_amount = if contract == self.dapp {
+                    self.stakes.get(&staker).copied().unwrap_or_default()
+                } else {
+                    0
+                };
+                staked_amount.encode_to(output);
+                0
+            }
+        }
+
+        fn register_mock_extension(era: u32, dapp: AccountId, stakes: Vec<(AccountId, Balance)>) {
+            ink::env::test::register_chain_extension(MockReadCurrentEra { era });
+            ink::env::test::register_chain_extension(
+                MockReadStakedAmountOnContract { dapp, stakes: stakes.into_iter().collect() }
+            );
+        }
+
+        #[ink::test]
+        fn test_add_participants_from_dapps_staking() {
+            let accounts = accounts();
+            let dapp = accounts.frank;
+            register_mock_extension(
+                5,
+                dapp,
+                vec![(accounts.alice, 1_000), (accounts.bob, 2_000), (accounts.charlie, 3_000)]
+            );
+
+            let mut contract = Contract::new(dapp);
+
+            // the participants are given by pages
+            contract.add_participants_from_dapps_staking(POOL_ID, 5, vec![accounts.alice, accounts.bob]).unwrap();
+            contract.add_participants_from_dapps_staking(POOL_ID, 5, vec![accounts.charlie, accounts.django]).unwrap();
+
+            // the participants are added for the current era of dApps Staking
+            assert_eq!(contract.get_current_era(POOL_ID), 5);
+            // django has no stake
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 5), 3);
+            assert_eq!(contract.get_total_value_for_era(POOL_ID, 5), 6_000);
+            // the stakes are read on chain
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(1_000));
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), Some(2_000));
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.charlie), Some(3_000));
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.django), None);
+        }
+
+        #[ink::test]
+        fn test_read_stakes() {
+            let accounts = accounts();
+            let dapp = accounts.frank;
+            register_mock_extension(5, dapp, vec![(accounts.alice, 1_000), (accounts.bob, 2_000)]);
+
+            let contract = Contract::new(dapp);
+
+            // the stakers without stake are ignored
+            assert_eq!(
+                contract._read_stakes(dapp, 5, vec![accounts.bob, accounts.charlie, accounts.alice]).unwrap(),
+                vec![(accounts.bob, 2_000), (accounts.alice, 1_000)]
+            );
+            assert_eq!(contract._read_stakes(accounts.eve, 5, vec![accounts.alice]).unwrap(), vec![]);
+            match contract._read_stakes(dapp, 4, vec![accounts.alice]) {
+                Err(DappsStakingReaderError::InvalidEra) => debug_println!("InvalidEra as expected"),
+                _ => panic!("Error 1"),
+            }
+        }
+
+        #[ink::test]
+        fn test_add_participants_from_dapps_staking_other_dapp() {
+            let accounts = accounts();
+            register_mock_extension(1, accounts.frank, vec![(accounts.alice, 1_000)]);
+
+            // the stakes on another dApp are not used
+            let mut contract = Contract::new(accounts.eve);
+            contract.add_participants_from_dapps_staking(POOL_ID, 1, vec![accounts.alice]).unwrap();
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 1), 0);
+        }
+
+        #[ink::test]
+        fn test_add_participants_from_dapps_staking_other_era() {
+            let accounts = accounts();
+            let dapp = accounts.frank;
+            register_mock_extension(5, dapp, vec![(accounts.alice, 1_000)]);
+
+            let mut contract = Contract::new(dapp);
+
+            // the page built for the previous era is not added in the current era
+            match contract.add_participants_from_dapps_staking(POOL_ID, 4, vec![accounts.alice]) {
+                Err(ContractError::DappsStakingReaderError(DappsStakingReaderError::InvalidEra)) => debug_println!("InvalidEra as expected"),
+                _ => panic!("Error 1"),
+            }
+            match contract.add_participants_from_dapps_staking(POOL_ID, 6, vec![accounts.alice]) {
+                Err(ContractError::DappsStakingReaderError(DappsStakingReaderError::InvalidEra)) => debug_println!("InvalidEra as expected"),
+                _ => panic!("Error 2"),
+            }
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 5), 0);
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 4), 0);
+        }
+    }
+}
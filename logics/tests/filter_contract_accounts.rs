@@ -0,0 +1,96 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+#[openbrush::contract]
+pub mod filter_contract_accounts {
+    use openbrush::contracts::access_control::{*, access_control};
+    use openbrush::traits::Storage;
+
+    use lucky::impls::{
+        *,
+        participant_filter::filter_contract_accounts,
+        participant_filter::filter_contract_accounts::*,
+        participant_filter::filter_latest_winners::PARTICIPANT_FILTER_MANAGER,
+    };
+    use lucky::traits::participant_filter::participant_filter::{ParticipantFilter, ParticipantFilterError};
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct Contract {
+        #[storage_field]
+        filter_contract_accounts: filter_contract_accounts::Data,
+        #[storage_field]
+        access: access_control::Data,
+    }
+
+    impl FilterContractAccounts for Contract {}
+    impl AccessControl for Contract{}
+
+    impl filter_contract_accounts::Internal for Contract {
+        fn _is_contract(&self, account: &AccountId) -> bool {
+            self.env().is_contract(account)
+        }
+    }
+
+    impl Contract {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let mut instance = Self::default();
+            let caller = instance.env().caller();
+            instance._init_with_admin(caller);
+            instance.grant_role(PARTICIPANT_FILTER_MANAGER, caller).expect("Should grant the role PARTICIPANT_FILTER_MANAGER");
+            instance
+        }
+    }
+
+    mod tests {
+        use ink::env::debug_println;
+        use openbrush::test_utils::accounts;
+
+        use super::*;
+
+        #[ink::test]
+        fn test_contracts_not_eligible() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            // charlie and django are smart contracts
+            ink::env::test::set_contract::<ink::env::DefaultEnvironment>(accounts.charlie);
+            ink::env::test::set_contract::<ink::env::DefaultEnvironment>(accounts.django);
+
+            let filter: &dyn ParticipantFilter = &ContractAccountsFilter(&contract);
+            assert!(filter.is_eligible(1, 1, &accounts.alice, 100));
+            assert!(!filter.is_eligible(1, 1, &accounts.charlie, 100));
+            assert!(!filter.is_eligible(1, 1, &accounts.django, 100));
+
+            // the contracts in the allowlist are eligible
+            contract.add_to_contract_allowlist(vec![accounts.django]).unwrap();
+            assert!(contract.is_contract_allowed(accounts.django));
+            let filter: &dyn ParticipantFilter = &ContractAccountsFilter(&contract);
+            assert!(!filter.is_eligible(1, 1, &accounts.charlie, 100));
+            assert!(filter.is_eligible(1, 1, &accounts.django, 100));
+
+            contract.remove_from_contract_allowlist(vec![accounts.django]).unwrap();
+            assert!(!contract.is_contract_allowed(accounts.django));
+            let filter: &dyn ParticipantFilter = &ContractAccountsFilter(&contract);
+            assert!(!filter.is_eligible(1, 1, &accounts.django, 100));
+        }
+
+        #[ink::test]
+        fn test_contract_allowlist_not_allowed() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            match contract.add_to_contract_allowlist(vec![accounts.charlie]) {
+                Err(ParticipantFilterError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Error 1"),
+            }
+            match contract.remove_from_contract_allowlist(vec![accounts.charlie]) {
+                Err(ParticipantFilterError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Error 2"),
+            }
+        }
+    }
+}
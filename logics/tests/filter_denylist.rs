@@ -0,0 +1,132 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+#[openbrush::contract]
+pub mod filter_denylist {
+    use openbrush::contracts::access_control::{*, access_control};
+    use openbrush::traits::Storage;
+
+    use lucky::impls::{
+        *,
+        participant_filter::filter_denylist::*,
+        participant_filter::filter_latest_winners::PARTICIPANT_FILTER_MANAGER,
+    };
+    use lucky::traits::participant_filter::participant_filter::{ParticipantFilter, ParticipantFilterError};
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct Contract {
+        #[storage_field]
+        filter_denylist: participant_filter::filter_denylist::Data,
+        #[storage_field]
+        access: access_control::Data,
+    }
+
+    impl FilterDenylist for Contract {}
+    impl AccessControl for Contract{}
+
+    impl participant_filter::filter_denylist::Internal for Contract {
+        fn _emit_added_to_denylist_event(&self, _account: AccountId){
+            // no event for the tests
+        }
+        fn _emit_removed_from_denylist_event(&self, _account: AccountId){
+            // no event for the tests
+        }
+    }
+
+    impl Contract {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let mut instance = Self::default();
+            let caller = instance.env().caller();
+            instance._init_with_admin(caller);
+            instance.grant_role(PARTICIPANT_FILTER_MANAGER, caller).expect("Should grant the role PARTICIPANT_FILTER_MANAGER");
+            instance
+        }
+    }
+
+    mod tests {
+        use ink::env::debug_println;
+        use openbrush::test_utils::accounts;
+
+        use super::*;
+
+        #[ink::test]
+        fn test_add_and_remove_accounts() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            contract.add_to_denylist(vec![accounts.alice, accounts.bob, accounts.charlie]).unwrap();
+            // the accounts already denied are ignored
+            contract.add_to_denylist(vec![accounts.bob, accounts.django]).unwrap();
+            assert_eq!(contract.get_nb_denied_accounts(), 4);
+            assert!(contract.is_denied(accounts.alice));
+            assert!(contract.is_denied(accounts.django));
+            assert!(!contract.is_denied(accounts.eve));
+
+            // the accounts not denied are ignored
+            contract.remove_from_denylist(vec![accounts.alice, accounts.eve]).unwrap();
+            assert_eq!(contract.get_nb_denied_accounts(), 3);
+            assert!(!contract.is_denied(accounts.alice));
+
+            // the last account takes the position of the removed account
+            assert_eq!(
+                contract.get_denied_accounts(0, 10),
+                vec![accounts.django, accounts.bob, accounts.charlie]
+            );
+
+            contract.remove_from_denylist(vec![accounts.charlie, accounts.bob, accounts.django]).unwrap();
+            assert_eq!(contract.get_nb_denied_accounts(), 0);
+            assert_eq!(contract.get_denied_accounts(0, 10).len(), 0);
+            assert!(!contract.is_denied(accounts.bob));
+        }
+
+        #[ink::test]
+        fn test_get_denied_accounts_by_page() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            let denied_accounts = vec![accounts.alice, accounts.bob, accounts.charlie, accounts.django, accounts.eve];
+            contract.add_to_denylist(denied_accounts.clone()).unwrap();
+
+            assert_eq!(contract.get_denied_accounts(0, 2), denied_accounts[0..2].to_vec());
+            assert_eq!(contract.get_denied_accounts(2, 2), denied_accounts[2..4].to_vec());
+            assert_eq!(contract.get_denied_accounts(4, 2), denied_accounts[4..5].to_vec());
+            assert_eq!(contract.get_denied_accounts(5, 2).len(), 0);
+            assert_eq!(contract.get_denied_accounts(u32::MAX, u16::MAX).len(), 0);
+        }
+
+        #[ink::test]
+        fn test_denied_accounts_not_eligible() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            contract.add_to_denylist(vec![accounts.bob]).unwrap();
+
+            // the denylist is used for all pools and eras
+            let filter: &dyn ParticipantFilter = &contract.filter_denylist;
+            assert!(filter.is_eligible(1, 1, &accounts.alice, 100));
+            assert!(!filter.is_eligible(1, 1, &accounts.bob, 100));
+            assert!(!filter.is_eligible(2, 5, &accounts.bob, 100));
+            // the weight is not modified
+            assert_eq!(filter.get_weight(1, 1, &accounts.alice, 100), 100);
+        }
+
+        #[ink::test]
+        fn test_denylist_not_allowed() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            match contract.add_to_denylist(vec![accounts.bob]) {
+                Err(ParticipantFilterError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Error 1"),
+            }
+            match contract.remove_from_denylist(vec![accounts.bob]) {
+                Err(ParticipantFilterError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Error 2"),
+            }
+        }
+    }
+}
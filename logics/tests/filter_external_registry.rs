@@ -0,0 +1,148 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+#[openbrush::contract]
+pub mod filter_external_registry {
+    use openbrush::contracts::access_control::{*, access_control};
+    use openbrush::traits::Storage;
+
+    use lucky::impls::{
+        *,
+        participant_filter::filter_external_registry::*,
+        raffle::RAFFLE_MANAGER,
+    };
+    use lucky::traits::participant_filter::participant_filter::{ParticipantFilter, ParticipantFilterError};
+
+    // Selector of PSP34::balance_of
+    const BALANCE_OF_SELECTOR : [u8; 4] = [0xcd, 0xe7, 0xe5, 0x5f];
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct Contract {
+        #[storage_field]
+        filter_external_registry: participant_filter::filter_external_registry::Data,
+        #[storage_field]
+        access: access_control::Data,
+        /// accounts eligible in the mock of the external registry
+        mock_registry: Vec<AccountId>,
+        /// number of calls to the mock of the external registry
+        nb_registry_calls: u32,
+    }
+
+    pub const POOL_ID: u32 = 1;
+
+    impl FilterExternalRegistry for Contract {}
+    impl AccessControl for Contract{}
+
+    impl participant_filter::filter_external_registry::Internal for Contract {
+        fn _call_eligibility_registry(&mut self, _registry: &EligibilityRegistry, account: AccountId) -> Result<bool, ParticipantFilterError> {
+            // mock of the external registry
+            self.nb_registry_calls += 1;
+            Ok(self.mock_registry.contains(&account))
+        }
+    }
+
+    impl Contract {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let mut instance = Self::default();
+            let caller = instance.env().caller();
+            instance._init_with_admin(caller);
+            instance.grant_role(RAFFLE_MANAGER, caller).expect("Should grant the role RAFFLE_MANAGER");
+            instance
+        }
+    }
+
+    mod tests {
+        use ink::env::debug_println;
+        use openbrush::test_utils::accounts;
+
+        use super::*;
+
+        #[ink::test]
+        fn test_set_eligibility_registry() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+            assert_eq!(contract.get_eligibility_registry(POOL_ID), None);
+
+            contract.set_eligibility_registry(POOL_ID, accounts.frank, BALANCE_OF_SELECTOR, RegistryKind::Psp34BalanceOf).unwrap();
+            assert_eq!(
+                contract.get_eligibility_registry(POOL_ID),
+                Some(EligibilityRegistry { address: accounts.frank, selector: BALANCE_OF_SELECTOR, kind: RegistryKind::Psp34BalanceOf })
+            );
+            // the registry is set by pool
+            assert_eq!(contract.get_eligibility_registry(2), None);
+        }
+
+        #[ink::test]
+        fn test_refresh_eligibility() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+            let participants = vec![(accounts.alice, 100), (accounts.bob, 200)];
+
+            match contract._refresh_eligibility(POOL_ID, 1, &participants) {
+                Err(ParticipantFilterError::EligibilityRegistryMissing) => debug_println!("EligibilityRegistryMissing as expected"),
+                _ => panic!("Error 1"),
+            }
+
+            contract.set_eligibility_registry(POOL_ID, accounts.frank, BALANCE_OF_SELECTOR, RegistryKind::Psp34BalanceOf).unwrap();
+            contract.mock_registry = vec![accounts.bob];
+
+            contract._refresh_eligibility(POOL_ID, 1, &participants).unwrap();
+            assert_eq!(contract.nb_registry_calls, 2);
+            assert_eq!(contract.get_cached_eligibility(POOL_ID, 1, accounts.alice), Some(false));
+            assert_eq!(contract.get_cached_eligibility(POOL_ID, 1, accounts.bob), Some(true));
+            assert_eq!(contract.get_cached_eligibility(POOL_ID, 1, accounts.charlie), None);
+
+            let filter: &dyn ParticipantFilter = &contract.filter_external_registry;
+            assert!(!filter.is_eligible(POOL_ID, 1, &accounts.alice, 100));
+            assert!(filter.is_eligible(POOL_ID, 1, &accounts.bob, 200));
+            // the accounts not checked for the era are not eligible
+            assert!(!filter.is_eligible(POOL_ID, 2, &accounts.bob, 200));
+        }
+
+        #[ink::test]
+        fn test_eligibility_cached_by_era() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+            let participants = vec![(accounts.alice, 100), (accounts.bob, 200)];
+
+            contract.set_eligibility_registry(POOL_ID, accounts.frank, BALANCE_OF_SELECTOR, RegistryKind::Psp34BalanceOf).unwrap();
+            contract.mock_registry = vec![accounts.bob];
+
+            contract._refresh_eligibility(POOL_ID, 1, &participants).unwrap();
+            assert_eq!(contract.nb_registry_calls, 2);
+
+            // the registry is not called again for the same era
+            contract.mock_registry = vec![accounts.alice, accounts.bob];
+            contract._refresh_eligibility(POOL_ID, 1, &participants).unwrap();
+            assert_eq!(contract.nb_registry_calls, 2);
+            assert_eq!(contract.get_cached_eligibility(POOL_ID, 1, accounts.alice), Some(false));
+
+            // but it is called for the next era
+            contract._refresh_eligibility(POOL_ID, 2, &participants).unwrap();
+            assert_eq!(contract.nb_registry_calls, 4);
+            assert_eq!(contract.get_cached_eligibility(POOL_ID, 2, accounts.alice), Some(true));
+
+            // the results cached with the previous registry are ignored
+            contract.set_eligibility_registry(POOL_ID, accounts.django, [0, 0, 0, 1], RegistryKind::IsEligible).unwrap();
+            assert_eq!(contract.get_cached_eligibility(POOL_ID, 1, accounts.alice), None);
+            contract._refresh_eligibility(POOL_ID, 1, &participants).unwrap();
+            assert_eq!(contract.nb_registry_calls, 6);
+            assert_eq!(contract.get_cached_eligibility(POOL_ID, 1, accounts.alice), Some(true));
+        }
+
+        #[ink::test]
+        fn test_set_eligibility_registry_not_allowed() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            match contract.set_eligibility_registry(POOL_ID, accounts.frank, BALANCE_OF_SELECTOR, RegistryKind::Psp34BalanceOf) {
+                Err(ParticipantFilterError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Error 1"),
+            }
+        }
+    }
+}
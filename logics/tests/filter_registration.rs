@@ -0,0 +1,136 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+#[openbrush::contract]
+pub mod filter_registration {
+    use openbrush::contracts::access_control::{*, access_control};
+    use openbrush::traits::Storage;
+
+    use lucky::impls::{
+        *,
+        participant_filter::filter_registration::*,
+        participant_filter::filter_latest_winners::PARTICIPANT_FILTER_MANAGER,
+    };
+    use lucky::traits::participant_filter::participant_filter::{ParticipantFilter, ParticipantFilterError};
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct Contract {
+        #[storage_field]
+        filter_registration: participant_filter::filter_registration::Data,
+        #[storage_field]
+        access: access_control::Data,
+        /// era used to timestamp the registrations
+        current_era: u32,
+    }
+
+    impl FilterRegistration for Contract {}
+    impl AccessControl for Contract{}
+
+    impl participant_filter::filter_registration::Internal for Contract {
+        fn _get_current_era(&self) -> u32 {
+            self.current_era
+        }
+        fn _emit_registered_event(&self, _account: AccountId, _era: u32){
+            // no event for the tests
+        }
+        fn _emit_unregistered_event(&self, _account: AccountId){
+            // no event for the tests
+        }
+    }
+
+    impl Contract {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let mut instance = Self::default();
+            let caller = instance.env().caller();
+            instance._init_with_admin(caller);
+            instance.grant_role(PARTICIPANT_FILTER_MANAGER, caller).expect("Should grant the role PARTICIPANT_FILTER_MANAGER");
+            instance
+        }
+    }
+
+    mod tests {
+        use ink::env::debug_println;
+        use openbrush::test_utils::accounts;
+
+        use super::*;
+
+        #[ink::test]
+        fn test_register_and_unregister() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            contract.current_era = 5;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.register().unwrap();
+            assert!(contract.is_registered(accounts.bob));
+            assert_eq!(contract.get_registration_era(accounts.bob), Some(5));
+
+            match contract.register() {
+                Err(ParticipantFilterError::AlreadyRegistered) => debug_println!("AlreadyRegistered as expected"),
+                _ => panic!("Error 1"),
+            }
+
+            contract.unregister().unwrap();
+            assert!(!contract.is_registered(accounts.bob));
+            assert_eq!(contract.get_registration_era(accounts.bob), None);
+
+            match contract.unregister() {
+                Err(ParticipantFilterError::NotRegistered) => debug_println!("NotRegistered as expected"),
+                _ => panic!("Error 2"),
+            }
+        }
+
+        #[ink::test]
+        fn test_register_accounts() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            contract.current_era = 5;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.register().unwrap();
+
+            // the accounts already registered are ignored
+            contract.current_era = 6;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.register_accounts(vec![accounts.bob, accounts.charlie]).unwrap();
+            assert_eq!(contract.get_registration_era(accounts.bob), Some(5));
+            assert_eq!(contract.get_registration_era(accounts.charlie), Some(6));
+            assert!(!contract.is_registered(accounts.alice));
+        }
+
+        #[ink::test]
+        fn test_registered_accounts_eligible_from_next_era() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            contract.current_era = 5;
+            contract.register_accounts(vec![accounts.bob]).unwrap();
+
+            let filter: &dyn ParticipantFilter = &contract.filter_registration;
+            assert!(!filter.is_eligible(1, 4, &accounts.bob, 100));
+            assert!(!filter.is_eligible(1, 5, &accounts.bob, 100));
+            assert!(filter.is_eligible(1, 6, &accounts.bob, 100));
+            assert!(filter.is_eligible(2, 6, &accounts.bob, 100));
+            // the accounts not registered are never eligible
+            assert!(!filter.is_eligible(1, 6, &accounts.alice, 100));
+        }
+
+        #[ink::test]
+        fn test_register_accounts_not_allowed() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            match contract.register_accounts(vec![accounts.bob]) {
+                Err(ParticipantFilterError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Error 1"),
+            }
+            // but the account can register itself
+            contract.register().unwrap();
+            assert!(contract.is_registered(accounts.bob));
+        }
+    }
+}
@@ -0,0 +1,248 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+#[openbrush::contract]
+pub mod filter_registry {
+    use openbrush::contracts::access_control::{*, access_control};
+    use openbrush::traits::Storage;
+
+    use lucky::impls::{
+        *,
+        participant_filter::filter_latest_winners,
+        participant_filter::filter_latest_winners::*,
+        participant_filter::filter_registry::*,
+        participant_filter::filter_denylist,
+        participant_filter::filter_denylist::*,
+        participant_filter::filter_registration,
+        participant_filter::filter_registration::*,
+        participant_filter::filter_winner_cooldown,
+        participant_filter::filter_winner_cooldown::*,
+        participant_filter::filter_winner_decay,
+        participant_filter::filter_winner_decay::*,
+        participant_filter::filter_contract_accounts,
+        participant_filter::filter_contract_accounts::*,
+        participant_filter::filter_external_registry,
+        participant_filter::filter_external_registry::*,
+    };
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct Contract {
+        #[storage_field]
+        filter_latest_winners: filter_latest_winners::Data,
+        #[storage_field]
+        filter_registry: participant_filter::filter_registry::Data,
+        #[storage_field]
+        filter_denylist: filter_denylist::Data,
+        #[storage_field]
+        filter_registration: filter_registration::Data,
+        #[storage_field]
+        filter_winner_cooldown: filter_winner_cooldown::Data,
+        #[storage_field]
+        filter_winner_decay: filter_winner_decay::Data,
+        #[storage_field]
+        filter_contract_accounts: filter_contract_accounts::Data,
+        #[storage_field]
+        filter_external_registry: filter_external_registry::Data,
+        /// current era used by the filters
+        current_era: u32,
+        #[storage_field]
+        access: access_control::Data,
+    }
+
+    pub const POOL_ID: u32 = 1;
+
+    impl FilterLatestWinners for Contract {}
+    impl FilterRegistry for Contract {}
+    impl FilterDenylist for Contract {}
+    impl FilterRegistration for Contract {}
+    impl FilterWinnerCooldown for Contract {}
+    impl FilterWinnerDecay for Contract {}
+    impl FilterContractAccounts for Contract {}
+    impl FilterExternalRegistry for Contract {}
+    impl AccessControl for Contract{}
+
+    impl filter_winner_cooldown::Internal for Contract {
+        fn _get_current_era(&self) -> u32 {
+            self.current_era
+        }
+    }
+
+    impl filter_external_registry::Internal for Contract {
+        fn _call_eligibility_registry(&mut self, _registry: &EligibilityRegistry, _account: AccountId) -> Result<bool, ParticipantFilterError> {
+            // no external registry for the tests
+            Ok(false)
+        }
+    }
+
+    impl filter_contract_accounts::Internal for Contract {
+        fn _is_contract(&self, account: &AccountId) -> bool {
+            self.env().is_contract(account)
+        }
+    }
+
+    impl participant_filter::filter_registry::Internal for Contract {
+        fn _get_participant_filter(&self, filter: FilterId) -> Box<dyn ParticipantFilter + '_> {
+            match filter {
+                FilterId::LatestWinners => Box::new(&self.filter_latest_winners),
+                FilterId::Denylist => Box::new(&self.filter_denylist),
+                FilterId::Registration => Box::new(&self.filter_registration),
+                FilterId::WinnerCooldown => Box::new(&self.filter_winner_cooldown),
+                FilterId::WinnerDecay => Box::new(WinnerDecayFilter(self)),
+                FilterId::ContractAccounts => Box::new(ContractAccountsFilter(self)),
+                FilterId::ExternalRegistry => Box::new(&self.filter_external_registry),
+            }
+        }
+    }
+
+    impl filter_registration::Internal for Contract {
+        fn _get_current_era(&self) -> u32 {
+            self.current_era
+        }
+        fn _emit_registered_event(&self, _account: AccountId, _era: u32){
+            // no event for the tests
+        }
+        fn _emit_unregistered_event(&self, _account: AccountId){
+            // no event for the tests
+        }
+    }
+
+    impl filter_denylist::Internal for Contract {
+        fn _emit_added_to_denylist_event(&self, _account: AccountId){
+            // no event for the tests
+        }
+        fn _emit_removed_from_denylist_event(&self, _account: AccountId){
+            // no event for the tests
+        }
+    }
+
+    impl Contract {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let mut instance = Self::default();
+            let caller = instance.env().caller();
+            instance._init_with_admin(caller);
+            instance.grant_role(PARTICIPANT_FILTER_MANAGER, caller).expect("Should grant the role PARTICIPANT_FILTER_MANAGER");
+            instance
+        }
+    }
+
+    mod tests {
+        use ink::env::debug_println;
+        use openbrush::test_utils::accounts;
+
+        use super::*;
+
+        #[ink::test]
+        fn test_enable_and_disable_filters() {
+            let mut contract = Contract::new();
+            // the latest winners are excluded by default
+            assert_eq!(contract.get_filters(POOL_ID), vec![FilterId::LatestWinners]);
+
+            match contract.enable_filter(POOL_ID, FilterId::LatestWinners) {
+                Err(ParticipantFilterError::FilterAlreadyEnabled) => debug_println!("FilterAlreadyEnabled as expected"),
+                _ => panic!("Error 1"),
+            }
+
+            contract.enable_filter(POOL_ID, FilterId::Denylist).unwrap();
+            assert_eq!(contract.get_filters(POOL_ID), vec![FilterId::LatestWinners, FilterId::Denylist]);
+            // the filters are enabled by pool
+            assert_eq!(contract.get_filters(2), vec![FilterId::LatestWinners]);
+
+            contract.disable_filter(POOL_ID, FilterId::LatestWinners).unwrap();
+            assert_eq!(contract.get_filters(POOL_ID), vec![FilterId::Denylist]);
+
+            match contract.disable_filter(POOL_ID, FilterId::LatestWinners) {
+                Err(ParticipantFilterError::FilterNotEnabled) => debug_println!("FilterNotEnabled as expected"),
+                _ => panic!("Error 2"),
+            }
+
+            contract.set_filters(POOL_ID, vec![FilterId::LatestWinners]).unwrap();
+            assert_eq!(contract.get_filters(POOL_ID), vec![FilterId::LatestWinners]);
+
+            match contract.set_filters(POOL_ID, vec![FilterId::LatestWinners, FilterId::LatestWinners]) {
+                Err(ParticipantFilterError::DuplicateFilter) => debug_println!("DuplicateFilter as expected"),
+                _ => panic!("Error 3"),
+            }
+
+            // all filters can be disabled
+            contract.set_filters(POOL_ID, vec![]).unwrap();
+            assert_eq!(contract.get_filters(POOL_ID).len(), 0);
+        }
+
+        #[ink::test]
+        fn test_apply_filters() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+            let participants = vec![(accounts.alice, 100), (accounts.bob, 200), (accounts.charlie, 300)];
+
+            contract.set_nb_winners_filtered(POOL_ID, 2).unwrap();
+            contract._add_winner(POOL_ID, accounts.bob);
+
+            // the latest winners are excluded by default
+            assert_eq!(
+                contract._apply_filters(POOL_ID, 1, participants.clone()),
+                (vec![(accounts.alice, 100), (accounts.charlie, 300)], vec![(accounts.bob, FilterId::LatestWinners)])
+            );
+
+            // no filter enabled
+            contract.set_filters(POOL_ID, vec![]).unwrap();
+            assert_eq!(contract._apply_filters(POOL_ID, 1, participants.clone()), (participants.clone(), vec![]));
+
+            contract.enable_filter(POOL_ID, FilterId::LatestWinners).unwrap();
+            assert_eq!(
+                contract._apply_filters(POOL_ID, 1, participants.clone()),
+                (vec![(accounts.alice, 100), (accounts.charlie, 300)], vec![(accounts.bob, FilterId::LatestWinners)])
+            );
+
+            // the winners of another pool are not filtered
+            assert_eq!(contract._apply_filters(2, 1, participants.clone()), (participants.clone(), vec![]));
+
+            // all filters of the chain are applied
+            contract.add_to_denylist(vec![accounts.charlie]).unwrap();
+            contract.enable_filter(POOL_ID, FilterId::Denylist).unwrap();
+            assert_eq!(contract.get_filters(POOL_ID), vec![FilterId::LatestWinners, FilterId::Denylist]);
+            assert_eq!(
+                contract._apply_filters(POOL_ID, 1, participants.clone()),
+                (vec![(accounts.alice, 100)], vec![(accounts.bob, FilterId::LatestWinners), (accounts.charlie, FilterId::Denylist)])
+            );
+        }
+
+        #[ink::test]
+        fn test_excluded_by_first_filter() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            // bob is a smart contract in the denylist
+            ink::env::test::set_contract::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.add_to_denylist(vec![accounts.bob]).unwrap();
+            contract.set_filters(POOL_ID, vec![FilterId::Denylist, FilterId::ContractAccounts]).unwrap();
+
+            // the account is reported once, by the filter which removed it
+            let (eligible, excluded) = contract._apply_filters(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.bob, 200)]);
+            assert_eq!(eligible, vec![(accounts.alice, 100)]);
+            assert_eq!(excluded, vec![(accounts.bob, FilterId::Denylist)]);
+
+            contract.remove_from_denylist(vec![accounts.bob]).unwrap();
+            let (_, excluded) = contract._apply_filters(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.bob, 200)]);
+            assert_eq!(excluded, vec![(accounts.bob, FilterId::ContractAccounts)]);
+        }
+
+        #[ink::test]
+        fn test_set_filters_not_allowed() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            match contract.enable_filter(POOL_ID, FilterId::LatestWinners) {
+                Err(ParticipantFilterError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Error 1"),
+            }
+            match contract.set_filters(POOL_ID, vec![FilterId::LatestWinners]) {
+                Err(ParticipantFilterError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Error 2"),
+            }
+        }
+    }
+}
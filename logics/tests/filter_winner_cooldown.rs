@@ -0,0 +1,123 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+#[openbrush::contract]
+pub mod filter_winner_cooldown {
+    use openbrush::contracts::access_control::{*, access_control};
+    use openbrush::traits::Storage;
+
+    use lucky::impls::{
+        *,
+        participant_filter::filter_winner_cooldown::*,
+        participant_filter::filter_latest_winners::PARTICIPANT_FILTER_MANAGER,
+    };
+    use lucky::traits::participant_filter::participant_filter::{ParticipantFilter, ParticipantFilterError};
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct Contract {
+        #[storage_field]
+        filter_winner_cooldown: participant_filter::filter_winner_cooldown::Data,
+        #[storage_field]
+        access: access_control::Data,
+        /// current era used by the filters
+        current_era: u32,
+    }
+
+    pub const POOL_ID: u32 = 1;
+
+    impl FilterWinnerCooldown for Contract {}
+    impl AccessControl for Contract{}
+
+    impl participant_filter::filter_winner_cooldown::Internal for Contract {
+        fn _get_current_era(&self) -> u32 {
+            self.current_era
+        }
+    }
+
+    impl Contract {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let mut instance = Self::default();
+            let caller = instance.env().caller();
+            instance._init_with_admin(caller);
+            instance.grant_role(PARTICIPANT_FILTER_MANAGER, caller).expect("Should grant the role PARTICIPANT_FILTER_MANAGER");
+            instance
+        }
+    }
+
+    mod tests {
+        use ink::env::debug_println;
+        use openbrush::test_utils::accounts;
+
+        use super::*;
+
+        #[ink::test]
+        fn test_winners_excluded_during_cooldown() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            contract.set_nb_cooldown_eras(POOL_ID, 2).unwrap();
+            assert_eq!(contract.get_nb_cooldown_eras(POOL_ID), 2);
+
+            contract._set_last_win_era(POOL_ID, accounts.bob, 10);
+            assert_eq!(contract.get_last_win_era(POOL_ID, accounts.bob), Some(10));
+
+            // Bob can't win again during the eras 11 and 12
+            let filter: &dyn ParticipantFilter = &contract.filter_winner_cooldown;
+            assert!(!filter.is_eligible(POOL_ID, 11, &accounts.bob, 100));
+            assert!(!filter.is_eligible(POOL_ID, 12, &accounts.bob, 100));
+            assert!(filter.is_eligible(POOL_ID, 13, &accounts.bob, 100));
+            // the other accounts and the other pools are not impacted
+            assert!(filter.is_eligible(POOL_ID, 11, &accounts.alice, 100));
+            assert!(filter.is_eligible(2, 11, &accounts.bob, 100));
+        }
+
+        #[ink::test]
+        fn test_get_cooldown_remaining() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            contract.set_nb_cooldown_eras(POOL_ID, 2).unwrap();
+            contract._set_last_win_era(POOL_ID, accounts.bob, 10);
+
+            contract.current_era = 11;
+            assert_eq!(contract.get_cooldown_remaining(POOL_ID, accounts.bob), 2);
+            contract.current_era = 12;
+            assert_eq!(contract.get_cooldown_remaining(POOL_ID, accounts.bob), 1);
+            contract.current_era = 13;
+            assert_eq!(contract.get_cooldown_remaining(POOL_ID, accounts.bob), 0);
+            assert_eq!(contract.get_cooldown_remaining(POOL_ID, accounts.alice), 0);
+
+            // the new cooldown is applied immediately
+            contract.current_era = 11;
+            contract.set_nb_cooldown_eras(POOL_ID, 0).unwrap();
+            assert_eq!(contract.get_cooldown_remaining(POOL_ID, accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn test_keep_the_last_win_era() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            contract._set_last_win_era(POOL_ID, accounts.bob, 10);
+            contract._set_last_win_era(POOL_ID, accounts.bob, 8);
+            assert_eq!(contract.get_last_win_era(POOL_ID, accounts.bob), Some(10));
+            contract._set_last_win_era(POOL_ID, accounts.bob, 12);
+            assert_eq!(contract.get_last_win_era(POOL_ID, accounts.bob), Some(12));
+        }
+
+        #[ink::test]
+        fn test_set_nb_cooldown_eras_not_allowed() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            match contract.set_nb_cooldown_eras(POOL_ID, 2) {
+                Err(ParticipantFilterError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Error 1"),
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+#[openbrush::contract]
+pub mod filter_winner_decay {
+    use openbrush::contracts::access_control::{*, access_control};
+    use openbrush::traits::Storage;
+
+    use lucky::impls::{
+        *,
+        participant_filter::filter_winner_cooldown,
+        participant_filter::filter_winner_cooldown::*,
+        participant_filter::filter_winner_decay::*,
+        participant_filter::filter_latest_winners::PARTICIPANT_FILTER_MANAGER,
+    };
+    use lucky::traits::participant_filter::participant_filter::{ParticipantFilter, ParticipantFilterError};
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct Contract {
+        #[storage_field]
+        filter_winner_cooldown: filter_winner_cooldown::Data,
+        #[storage_field]
+        filter_winner_decay: participant_filter::filter_winner_decay::Data,
+        #[storage_field]
+        access: access_control::Data,
+    }
+
+    pub const POOL_ID: u32 = 1;
+
+    impl FilterWinnerCooldown for Contract {}
+    impl FilterWinnerDecay for Contract {}
+    impl AccessControl for Contract{}
+
+    impl filter_winner_cooldown::Internal for Contract {
+        fn _get_current_era(&self) -> u32 {
+            0
+        }
+    }
+
+
+    impl Contract {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let mut instance = Self::default();
+            let caller = instance.env().caller();
+            instance._init_with_admin(caller);
+            instance.grant_role(PARTICIPANT_FILTER_MANAGER, caller).expect("Should grant the role PARTICIPANT_FILTER_MANAGER");
+            instance
+        }
+    }
+
+    mod tests {
+        use ink::env::debug_println;
+        use openbrush::test_utils::accounts;
+
+        use super::*;
+
+        #[ink::test]
+        fn test_set_decay_schedule() {
+            let mut contract = Contract::new();
+            assert_eq!(contract.get_decay_schedule(POOL_ID).len(), 0);
+
+            contract.set_decay_schedule(POOL_ID, vec![50, 75]).unwrap();
+            assert_eq!(contract.get_decay_schedule(POOL_ID), vec![50, 75]);
+            // the schedule is set by pool
+            assert_eq!(contract.get_decay_schedule(2).len(), 0);
+
+            match contract.set_decay_schedule(POOL_ID, vec![50, 101]) {
+                Err(ParticipantFilterError::InvalidDecayPercentage) => debug_println!("InvalidDecayPercentage as expected"),
+                _ => panic!("Error 1"),
+            }
+            assert_eq!(contract.get_decay_schedule(POOL_ID), vec![50, 75]);
+        }
+
+        #[ink::test]
+        fn test_weight_reduced_after_win() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            contract.set_decay_schedule(POOL_ID, vec![50, 75]).unwrap();
+            contract._set_last_win_era(POOL_ID, accounts.bob, 10);
+
+            let filter: &dyn ParticipantFilter = &WinnerDecayFilter(&contract);
+            // the winners are never excluded
+            assert!(filter.is_eligible(POOL_ID, 11, &accounts.bob, 1000));
+
+            assert_eq!(filter.get_weight(POOL_ID, 10, &accounts.bob, 1000), 1000);
+            assert_eq!(filter.get_weight(POOL_ID, 11, &accounts.bob, 1000), 500);
+            assert_eq!(filter.get_weight(POOL_ID, 12, &accounts.bob, 1000), 750);
+            assert_eq!(filter.get_weight(POOL_ID, 13, &accounts.bob, 1000), 1000);
+            // the other accounts and the other pools are not impacted
+            assert_eq!(filter.get_weight(POOL_ID, 11, &accounts.alice, 1000), 1000);
+            assert_eq!(filter.get_weight(2, 11, &accounts.bob, 1000), 1000);
+            // no overflow with a huge weight
+            assert_eq!(filter.get_weight(POOL_ID, 11, &accounts.bob, Balance::MAX), Balance::MAX / 2);
+        }
+
+        #[ink::test]
+        fn test_weight_based_on_last_win() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            contract.set_decay_schedule(POOL_ID, vec![50, 75]).unwrap();
+            contract._set_last_win_era(POOL_ID, accounts.bob, 10);
+            contract._set_last_win_era(POOL_ID, accounts.bob, 11);
+            // an older win is ignored
+            contract._set_last_win_era(POOL_ID, accounts.bob, 9);
+
+            let filter: &dyn ParticipantFilter = &WinnerDecayFilter(&contract);
+            assert_eq!(filter.get_weight(POOL_ID, 12, &accounts.bob, 1000), 500);
+            assert_eq!(filter.get_weight(POOL_ID, 13, &accounts.bob, 1000), 750);
+        }
+
+        #[ink::test]
+        fn test_set_decay_schedule_not_allowed() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            match contract.set_decay_schedule(POOL_ID, vec![50]) {
+                Err(ParticipantFilterError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Error 1"),
+            }
+        }
+    }
+}
@@ -0,0 +1,267 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+#[cfg(test)]
+#[openbrush::contract]
+pub mod merkle_participants {
+    use openbrush::contracts::access_control::{*, access_control};
+    use openbrush::traits::Storage;
+
+    use lucky::impls::{
+        *,
+        merkle_participants::*,
+        participant_manager::PARTICIPANT_MANAGER,
+    };
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct Contract {
+        #[storage_field]
+        merkle_participants: merkle_participants::Data,
+        #[storage_field]
+        access: access_control::Data,
+    }
+
+    pub const POOL_ID: u32 = 1;
+
+    impl MerkleParticipants for Contract {}
+    impl AccessControl for Contract{}
+
+    impl Contract {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let mut instance = Self::default();
+            let caller = instance.env().caller();
+            instance._init_with_admin(caller);
+            instance.grant_role(PARTICIPANT_MANAGER, caller).expect("Should grant the role PARTICIPANT_MANAGER");
+            instance
+        }
+
+    }
+
+    mod tests {
+        use ink::env::debug_println;
+
+        use super::*;
+
+        /// generate the participants with different weights
+        fn generate_participants(nb_participants: u32) -> Vec<(AccountId, Balance)> {
+            (0..nb_participants)
+                .map(|i| {
+                    let mut account = [0u8; 32];
+                    account[..4].copy_from_slice(&i.to_be_bytes());
+                    (AccountId::from(account), (i as Balance % 7 + 1) * 100)
+                })
+                .collect()
+        }
+
+        #[ink::test]
+        fn test_generated_trees() {
+            for nb_participants in [1, 2, 3, 4, 5, 7, 8, 9, 16, 33, 100] {
+                let participants = generate_participants(nb_participants);
+                let tree = ParticipantsMerkleTree::new(&participants);
+
+                let total_value: Balance = participants.iter().map(|(_, value)| value).sum();
+                assert_eq!(tree.total_value(), total_value);
+                assert_eq!(tree.leaves().len(), nb_participants as usize);
+
+                for (index, leaf) in tree.leaves().iter().enumerate() {
+                    assert_eq!(leaf.account, participants[index].0);
+                    assert_eq!(leaf.end - leaf.start, participants[index].1);
+
+                    // all leaves can be proven
+                    let proof = tree.get_proof(index);
+                    assert_eq!(verify_proof(&tree.root(), leaf, &proof), Some(total_value));
+
+                    // the leaf covers the weights in ]start, end]
+                    assert_eq!(tree.find_leaf(leaf.start + 1), Some(index));
+                    assert_eq!(tree.find_leaf(leaf.end), Some(index));
+                }
+                assert_eq!(tree.find_leaf(0), None);
+                assert_eq!(tree.find_leaf(total_value + 1), None);
+            }
+        }
+
+        #[ink::test]
+        fn test_invalid_proofs() {
+            let participants = generate_participants(10);
+            let tree = ParticipantsMerkleTree::new(&participants);
+            let root = tree.root();
+
+            let leaf = tree.leaves()[3].clone();
+            let proof = tree.get_proof(3);
+            assert_eq!(verify_proof(&root, &leaf, &proof), Some(tree.total_value()));
+
+            // the weights cannot be changed
+            let mut wrong_leaf = leaf.clone();
+            wrong_leaf.end += 1;
+            assert_eq!(verify_proof(&root, &wrong_leaf, &proof), None);
+
+            // the account cannot be changed
+            let mut wrong_leaf = leaf.clone();
+            wrong_leaf.account = participants[4].0;
+            assert_eq!(verify_proof(&root, &wrong_leaf, &proof), None);
+
+            // the proof of another leaf is not valid
+            assert_eq!(verify_proof(&root, &leaf, &tree.get_proof(4)), None);
+
+            // the proof cannot be truncated
+            assert_eq!(verify_proof(&root, &leaf, &proof[..proof.len() - 1]), None);
+
+            // the weight of a sibling cannot be changed
+            let mut wrong_proof = proof.clone();
+            wrong_proof[0].value += 1;
+            assert_eq!(verify_proof(&root, &leaf, &wrong_proof), None);
+
+            // the root of another tree is not valid
+            let other_tree = ParticipantsMerkleTree::new(&generate_participants(11));
+            assert_eq!(verify_proof(&other_tree.root(), &leaf, &proof), None);
+        }
+
+        /// set the root of the tree with the proof of its last leaf
+        fn set_root(contract: &mut Contract, era: u32, tree: &ParticipantsMerkleTree) -> Result<(), MerkleParticipantsError> {
+            let index = tree.leaves().len() - 1;
+            contract.set_participants_root(POOL_ID, era, tree.root(), tree.leaves().len() as u32, tree.leaves()[index].clone(), tree.get_proof(index))
+        }
+
+        #[ink::test]
+        fn test_set_root_with_last_leaf() {
+            let mut contract = Contract::new();
+
+            let tree = ParticipantsMerkleTree::new(&generate_participants(10));
+
+            // another leaf than the last one does not give the total weight
+            match contract.set_participants_root(POOL_ID, 1, tree.root(), 10, tree.leaves()[8].clone(), tree.get_proof(8)) {
+                Err(MerkleParticipantsError::InvalidProof) => debug_println!("InvalidProof as expected"),
+                _ => panic!("Error 1"),
+            }
+
+            // the end of the last leaf cannot be changed
+            let mut wrong_leaf = tree.leaves()[9].clone();
+            wrong_leaf.end += 1;
+            match contract.set_participants_root(POOL_ID, 1, tree.root(), 10, wrong_leaf, tree.get_proof(9)) {
+                Err(MerkleParticipantsError::InvalidProof) => debug_println!("InvalidProof as expected"),
+                _ => panic!("Error 2"),
+            }
+
+            // the number of participants must be consistent with the proof of the last leaf
+            for nb_participants in [0, 9, 12, 16] {
+                match contract.set_participants_root(POOL_ID, 1, tree.root(), nb_participants, tree.leaves()[9].clone(), tree.get_proof(9)) {
+                    Err(MerkleParticipantsError::InvalidProof) => debug_println!("InvalidProof as expected"),
+                    _ => panic!("Error 3"),
+                }
+            }
+            assert_eq!(contract.get_participants_root(POOL_ID, 1), None);
+
+            set_root(&mut contract, 1, &tree).unwrap();
+            assert_eq!(contract.get_participants_root(POOL_ID, 1).unwrap().total_value, tree.total_value());
+            assert_eq!(contract.get_participants_root(POOL_ID, 1).unwrap().nb_participants, 10);
+
+            // tree with only one leaf
+            let tree = ParticipantsMerkleTree::new(&generate_participants(1));
+            set_root(&mut contract, 2, &tree).unwrap();
+            assert_eq!(contract.get_participants_root(POOL_ID, 2).unwrap().total_value, 100);
+        }
+
+        #[ink::test]
+        fn test_prove_winners() {
+            let mut contract = Contract::new();
+
+            let participants = generate_participants(50);
+            let tree = ParticipantsMerkleTree::new(&participants);
+            let era = 1;
+
+            set_root(&mut contract, era, &tree).unwrap();
+            assert_eq!(
+                contract.get_participants_root(POOL_ID, era),
+                Some(ParticipantsRoot { root: tree.root(), nb_participants: 50, total_value: tree.total_value() })
+            );
+
+            let offsets = vec![(1, 500), (tree.total_value(), 300), (tree.total_value() / 2, 200)];
+            contract._publish_winning_offsets(POOL_ID, era, offsets.clone()).unwrap();
+            assert_eq!(contract.get_winning_offsets(POOL_ID, era).len(), 3);
+
+            for (index, (offset, reward)) in offsets.into_iter().enumerate() {
+                let leaf_index = tree.find_leaf(offset).unwrap();
+                let leaf = tree.leaves()[leaf_index].clone();
+                let account = leaf.account;
+                let proven_reward = contract._prove_winner(POOL_ID, era, index as u32, leaf, tree.get_proof(leaf_index)).unwrap();
+                assert_eq!(proven_reward, reward);
+                assert_eq!(contract.get_winning_offsets(POOL_ID, era)[index].winner, Some(account));
+            }
+
+            // the winner cannot be proven twice
+            let leaf = tree.leaves()[0].clone();
+            match contract._prove_winner(POOL_ID, era, 0, leaf, tree.get_proof(0)) {
+                Err(MerkleParticipantsError::OffsetAlreadyProven) => debug_println!("OffsetAlreadyProven as expected"),
+                _ => panic!("Error 1"),
+            }
+        }
+
+        #[ink::test]
+        fn test_prove_wrong_winner() {
+            let mut contract = Contract::new();
+
+            let participants = generate_participants(20);
+            let tree = ParticipantsMerkleTree::new(&participants);
+            let era = 1;
+
+            let leaf = tree.leaves()[5].clone();
+            match contract._prove_winner(POOL_ID, era, 0, leaf.clone(), tree.get_proof(5)) {
+                Err(MerkleParticipantsError::RootNotFound) => debug_println!("RootNotFound as expected"),
+                _ => panic!("Error 1"),
+            }
+
+            set_root(&mut contract, era, &tree).unwrap();
+            contract._publish_winning_offsets(POOL_ID, era, vec![(leaf.end, 1000)]).unwrap();
+
+            match contract._prove_winner(POOL_ID, era, 1, leaf.clone(), tree.get_proof(5)) {
+                Err(MerkleParticipantsError::OffsetNotFound) => debug_println!("OffsetNotFound as expected"),
+                _ => panic!("Error 2"),
+            }
+
+            // the next leaf does not cover the offset
+            let next_leaf = tree.leaves()[6].clone();
+            match contract._prove_winner(POOL_ID, era, 0, next_leaf, tree.get_proof(6)) {
+                Err(MerkleParticipantsError::OffsetNotCovered) => debug_println!("OffsetNotCovered as expected"),
+                _ => panic!("Error 3"),
+            }
+
+            // a leaf not in the tree covering the offset
+            let mut wrong_leaf = leaf.clone();
+            wrong_leaf.account = participants[6].0;
+            match contract._prove_winner(POOL_ID, era, 0, wrong_leaf, tree.get_proof(5)) {
+                Err(MerkleParticipantsError::InvalidProof) => debug_println!("InvalidProof as expected"),
+                _ => panic!("Error 4"),
+            }
+
+            contract._prove_winner(POOL_ID, era, 0, leaf, tree.get_proof(5)).unwrap();
+        }
+
+        #[ink::test]
+        fn test_root_and_offsets_set_once() {
+            let mut contract = Contract::new();
+
+            let tree = ParticipantsMerkleTree::new(&generate_participants(5));
+
+            match contract._publish_winning_offsets(POOL_ID, 1, vec![(1, 1000)]) {
+                Err(MerkleParticipantsError::RootNotFound) => debug_println!("RootNotFound as expected"),
+                _ => panic!("Error 1"),
+            }
+
+            set_root(&mut contract, 1, &tree).unwrap();
+            match contract.set_participants_root(POOL_ID, 1, [0u8; 32], 1, tree.leaves()[0].clone(), Vec::new()) {
+                Err(MerkleParticipantsError::RootAlreadySet) => debug_println!("RootAlreadySet as expected"),
+                _ => panic!("Error 2"),
+            }
+
+            contract._publish_winning_offsets(POOL_ID, 1, vec![(1, 1000)]).unwrap();
+            match contract._publish_winning_offsets(POOL_ID, 1, vec![(2, 1000)]) {
+                Err(MerkleParticipantsError::OffsetsAlreadyPublished) => debug_println!("OffsetsAlreadyPublished as expected"),
+                _ => panic!("Error 3"),
+            }
+            assert_eq!(contract.get_winning_offsets(POOL_ID, 1)[0].offset, 1);
+            assert_eq!(contract.get_winning_offsets(POOL_ID, 2).len(), 0);
+        }
+    }
+}
@@ -21,16 +21,42 @@ pub mod participant_manager {
         access: access_control::Data,
     }
 
+    pub const POOL_ID: u32 = 1;
+    pub const PAGE_SIZE: u32 = 300;
+    pub const MAX_PART: usize = PAGE_SIZE as usize * 6;
+
     impl ParticipantManager for Contract {}
     impl AccessControl for Contract{}
 
+    impl participant_manager::Internal for Contract {
+        fn _emit_participants_added_event(&self, _pool_id: u32, _era: u32, _nb_accepted: u32, _nb_rejected: u32, _added_value: Balance, _total_value: Balance){
+            // no event for the tests
+        }
+        fn _emit_participant_updated_event(&self, _pool_id: u32, _era: u32, _account: AccountId, _old_value: Balance, _new_value: Balance){
+            // no event for the tests
+        }
+        fn _emit_participant_removed_event(&self, _pool_id: u32, _era: u32, _account: AccountId, _value: Balance){
+            // no event for the tests
+        }
+        fn _emit_data_cleared_event(&self, _pool_id: u32, _era: u32){
+            // no event for the tests
+        }
+    }
+
     impl Contract {
         #[ink(constructor)]
         pub fn new() -> Self {
+            Self::new_with_config(PAGE_SIZE, MAX_PART as u32)
+        }
+
+        #[ink(constructor)]
+        pub fn new_with_config(page_size: u32, max_participants: u32) -> Self {
             let mut instance = Self::default();
             let caller = instance.env().caller();
             instance._init_with_admin(caller);
+            instance._init_participant_manager(page_size, max_participants).expect("Should init the participant manager");
             instance.grant_role(PARTICIPANT_MANAGER, caller).expect("Should grant the role ORACLE_DATA_MANAGER");
+            instance._create_pool(POOL_ID).expect("Should create the pool");
             instance
         }
 
@@ -47,16 +73,101 @@ pub mod participant_manager {
         fn test_0_participants() {
             let contract = Contract::new();
 
-            assert_eq!(contract.get_nb_participants(), 0);
-            assert_eq!(contract.get_total_value(), 0);
+            assert_eq!(contract.get_nb_participants(POOL_ID), 0);
+            assert_eq!(contract.get_total_value(POOL_ID), 0);
+
+            match contract.get_participants(POOL_ID, 0) {
+                Err(ParticipantManagerError::PageNotFound) => debug_println!("Page not found as expected"),
+                _ => panic!("The page 0 should not exist"),
+            }
+            assert_eq!(contract.get_participants(POOL_ID, 1).unwrap().len(), 0);
+            assert_eq!(contract.get_participants(POOL_ID, 2).unwrap().len(), 0);
+            assert_eq!(contract.get_participants(POOL_ID, 3).unwrap().len(), 0);
+            assert_eq!(contract.get_participants(POOL_ID, 4).unwrap().len(), 0);
+            assert_eq!(contract.get_participants(POOL_ID, 5).unwrap().len(), 0);
+            assert_eq!(contract.get_participants(POOL_ID, 6).unwrap().len(), 0);
+
+            match contract.get_participants(POOL_ID, 7) {
+                Err(ParticipantManagerError::PageNotFound) => debug_println!("Page not found as expected"),
+                _ => panic!("The page 7 should not exist"),
+            }
+        }
+
+        #[ink::test]
+        fn test_init_participant_manager() {
+            let mut contract = Contract::default();
+
+            match contract._init_participant_manager(0, 10) {
+                Err(ParticipantManagerError::InvalidPageSize) => debug_println!("InvalidPageSize as expected"),
+                _ => panic!("Error 1"),
+            }
+            match contract._init_participant_manager(10, 9) {
+                Err(ParticipantManagerError::InvalidMaxParticipants) => debug_println!("InvalidMaxParticipants as expected"),
+                _ => panic!("Error 2"),
+            }
+            match contract._init_participant_manager(10, MAX_PARTICIPANTS_LIMIT + 1) {
+                Err(ParticipantManagerError::InvalidMaxParticipants) => debug_println!("InvalidMaxParticipants as expected"),
+                _ => panic!("Error 3"),
+            }
+
+            // a page must fit in the storage buffer
+            match contract._init_participant_manager(MAX_PAGE_SIZE + 1, MAX_PARTICIPANTS_LIMIT) {
+                Err(ParticipantManagerError::InvalidPageSize) => debug_println!("InvalidPageSize as expected"),
+                _ => panic!("Error 4"),
+            }
+
+            contract._init_participant_manager(MAX_PAGE_SIZE, MAX_PARTICIPANTS_LIMIT).unwrap();
+            assert_eq!(contract.get_page_size(), MAX_PAGE_SIZE);
+            assert_eq!(contract.get_max_participants(), MAX_PARTICIPANTS_LIMIT);
+        }
+
+        #[ink::test]
+        fn test_many_pages() {
+            let page_size = 10;
+            let max_participants = 1000;
+            let mut contract = Contract::new_with_config(page_size, max_participants);
+            assert_eq!(contract.get_page_size(), page_size);
+            assert_eq!(contract.get_max_participants(), max_participants);
+
+            let accounts = accounts();
+            // batches not aligned with the page size
+            for _i in 0..(max_participants / 4) {
+                contract.add_participants(
+                    POOL_ID,
+                    1,
+                    vec![(accounts.alice, 1), (accounts.bob, 2),
+                         (accounts.charlie, 3), (accounts.django, 4)]
+                ).unwrap();
+            }
+            assert_eq!(contract.get_nb_participants(POOL_ID), max_participants);
+            assert_eq!(contract.get_total_value(POOL_ID), 10 * max_participants as u128 / 4);
+
+            for page in 1..=(max_participants / page_size) {
+                assert_eq!(contract.get_participants(POOL_ID, page).unwrap().len(), page_size as usize);
+            }
+            match contract.get_participants(POOL_ID, max_participants / page_size + 1) {
+                Err(ParticipantManagerError::PageNotFound) => debug_println!("Page not found as expected"),
+                _ => panic!("The page should not exist"),
+            }
+
+            assert_eq!(contract.get_participant(POOL_ID, 1).unwrap(), accounts.alice);
+            assert_eq!(contract.get_participant(POOL_ID, 2).unwrap(), accounts.bob);
+            assert_eq!(contract.get_participant(POOL_ID, 2495).unwrap(), accounts.charlie);
+            assert_eq!(contract.get_participant(POOL_ID, 2500).unwrap(), accounts.django);
+            assert_eq!(contract.get_participant(POOL_ID, 2501), None);
+
+            match contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 100)]) {
+                Err(ParticipantManagerError::MaxSizeExceeded) => debug_println!("Max size exceeded"),
+                _ => panic!("We should exceed the max size limit"),
+            }
 
-            assert_eq!(contract.get_participants(0).unwrap().len(), 0);
-            assert_eq!(contract.get_participants(1).unwrap().len(), 0);
-            assert_eq!(contract.get_participants(2).unwrap().len(), 0);
-            assert_eq!(contract.get_participants(3).unwrap().len(), 0);
-            assert_eq!(contract.get_participants(4).unwrap().len(), 0);
-            assert_eq!(contract.get_participants(5).unwrap().len(), 0);
-            assert_eq!(contract.get_participants(6).unwrap().len(), 0);
+            contract.clear_data(POOL_ID).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 0);
+            assert_eq!(contract.get_total_value(POOL_ID), 0);
+            for page in 1..=(max_participants / page_size) {
+                assert_eq!(contract.get_participants(POOL_ID, page).unwrap().len(), 0);
+            }
+            assert_eq!(contract.get_participant(POOL_ID, 1), None);
         }
 
 
@@ -71,14 +182,16 @@ pub mod participant_manager {
             let account_4 = accounts.django;
 
             contract.add_participants(
+                POOL_ID,
+                1,
                 vec![(account_1, 100), (account_2, 200),
                      (account_3, 300), (account_4, 400)]
             ).unwrap();
 
-            assert_eq!(contract.get_nb_participants(), 4);
-            assert_eq!(contract.get_total_value(), 100 + 200 + 300 + 400);
+            assert_eq!(contract.get_nb_participants(POOL_ID), 4);
+            assert_eq!(contract.get_total_value(POOL_ID), 100 + 200 + 300 + 400);
 
-            let participants = contract.get_participants(1).unwrap();
+            let participants = contract.get_participants(POOL_ID, 1).unwrap();
             assert_eq!(participants.len(), 4);
             assert_eq!(participants[0].account, account_1);
             assert_eq!(participants[0].value, 100);
@@ -89,20 +202,20 @@ pub mod participant_manager {
             assert_eq!(participants[3].account, account_4);
             assert_eq!(participants[3].value, 400);
 
-            assert_eq!(contract.get_participant(0).unwrap(), account_1);
-            assert_eq!(contract.get_participant(50).unwrap(), account_1);
-            assert_eq!(contract.get_participant(100).unwrap(), account_1);
-            assert_eq!(contract.get_participant(101).unwrap(), account_2);
-            assert_eq!(contract.get_participant(200).unwrap(), account_2);
-            assert_eq!(contract.get_participant(300).unwrap(), account_2);
-            assert_eq!(contract.get_participant(301).unwrap(), account_3);
-            assert_eq!(contract.get_participant(400).unwrap(), account_3);
-            assert_eq!(contract.get_participant(600).unwrap(), account_3);
-            assert_eq!(contract.get_participant(601).unwrap(), account_4);
-            assert_eq!(contract.get_participant(999).unwrap(), account_4);
-            assert_eq!(contract.get_participant(1000).unwrap(), account_4);
-
-            match contract.get_participant(1001) {
+            assert_eq!(contract.get_participant(POOL_ID, 0).unwrap(), account_1);
+            assert_eq!(contract.get_participant(POOL_ID, 50).unwrap(), account_1);
+            assert_eq!(contract.get_participant(POOL_ID, 100).unwrap(), account_1);
+            assert_eq!(contract.get_participant(POOL_ID, 101).unwrap(), account_2);
+            assert_eq!(contract.get_participant(POOL_ID, 200).unwrap(), account_2);
+            assert_eq!(contract.get_participant(POOL_ID, 300).unwrap(), account_2);
+            assert_eq!(contract.get_participant(POOL_ID, 301).unwrap(), account_3);
+            assert_eq!(contract.get_participant(POOL_ID, 400).unwrap(), account_3);
+            assert_eq!(contract.get_participant(POOL_ID, 600).unwrap(), account_3);
+            assert_eq!(contract.get_participant(POOL_ID, 601).unwrap(), account_4);
+            assert_eq!(contract.get_participant(POOL_ID, 999).unwrap(), account_4);
+            assert_eq!(contract.get_participant(POOL_ID, 1000).unwrap(), account_4);
+
+            match contract.get_participant(POOL_ID, 1001) {
                 None => debug_println!("As expected, no participant found when weight is too much"),
                 _ => panic!("We should not find participant"),
             }
@@ -119,27 +232,788 @@ pub mod participant_manager {
             let account_4 = accounts.django;
             for _i in 0..100 {
                 contract.add_participants(
+                    POOL_ID,
+                    1,
                     vec![(account_1, 100), (account_2, 200),
                          (account_3, 300), (account_4, 400)]
                 ).unwrap();
             }
-            //assert_eq!(contract.get_nb_participants(), 1200);
+            //assert_eq!(contract.get_nb_participants(POOL_ID), 1200);
 
             for _i in 0..1 {
-                //assert_eq!(contract.get_participant(0 * i).unwrap(), account_1);
-                assert_eq!(contract.get_participant(50).unwrap(), account_1);
-                //assert_eq!(contract.get_participant(100 * i).unwrap(), account_1);
-                //assert_eq!(contract.get_participant(101 * i).unwrap(), account_2);
-                //assert_eq!(contract.get_participant(200 * i).unwrap(), account_2);
-                //assert_eq!(contract.get_participant(300 * i).unwrap(), account_2);
-                //assert_eq!(contract.get_participant(301 * i).unwrap(), account_3);
-                //assert_eq!(contract.get_participant(400 * i).unwrap(), account_3);
-                //assert_eq!(contract.get_participant(600* i).unwrap(), account_3);
-                //assert_eq!(contract.get_participant(601* i).unwrap(), account_4);
-                //assert_eq!(contract.get_participant(999 * i).unwrap(), account_4);
-                //assert_eq!(contract.get_participant(1000 * i).unwrap(), account_4);
+                //assert_eq!(contract.get_participant(POOL_ID, 0 * i).unwrap(), account_1);
+                assert_eq!(contract.get_participant(POOL_ID, 50).unwrap(), account_1);
+                //assert_eq!(contract.get_participant(POOL_ID, 100 * i).unwrap(), account_1);
+                //assert_eq!(contract.get_participant(POOL_ID, 101 * i).unwrap(), account_2);
+                //assert_eq!(contract.get_participant(POOL_ID, 200 * i).unwrap(), account_2);
+                //assert_eq!(contract.get_participant(POOL_ID, 300 * i).unwrap(), account_2);
+                //assert_eq!(contract.get_participant(POOL_ID, 301 * i).unwrap(), account_3);
+                //assert_eq!(contract.get_participant(POOL_ID, 400 * i).unwrap(), account_3);
+                //assert_eq!(contract.get_participant(POOL_ID, 600* i).unwrap(), account_3);
+                //assert_eq!(contract.get_participant(POOL_ID, 601* i).unwrap(), account_4);
+                //assert_eq!(contract.get_participant(POOL_ID, 999 * i).unwrap(), account_4);
+                //assert_eq!(contract.get_participant(POOL_ID, 1000 * i).unwrap(), account_4);
+            }
+
+        }
+
+        #[ink::test]
+        fn test_get_participant_in_all_vectors() {
+            let mut contract = Contract::new();
+
+            let accounts = accounts();
+            let nb_iter = MAX_PART / 4;
+            for _i in 0..nb_iter {
+                contract.add_participants(
+                    POOL_ID,
+                    1,
+                    vec![(accounts.alice, 100), (accounts.bob, 200),
+                         (accounts.charlie, 300), (accounts.django, 400)]
+                ).unwrap();
+            }
+            assert_eq!(contract.get_total_value(POOL_ID), 1000 * nb_iter as u128);
+
+            for i in 0..nb_iter as u128 {
+                assert_eq!(contract.get_participant(POOL_ID, 1000 * i + 1).unwrap(), accounts.alice);
+                assert_eq!(contract.get_participant(POOL_ID, 1000 * i + 100).unwrap(), accounts.alice);
+                assert_eq!(contract.get_participant(POOL_ID, 1000 * i + 101).unwrap(), accounts.bob);
+                assert_eq!(contract.get_participant(POOL_ID, 1000 * i + 300).unwrap(), accounts.bob);
+                assert_eq!(contract.get_participant(POOL_ID, 1000 * i + 301).unwrap(), accounts.charlie);
+                assert_eq!(contract.get_participant(POOL_ID, 1000 * i + 600).unwrap(), accounts.charlie);
+                assert_eq!(contract.get_participant(POOL_ID, 1000 * i + 601).unwrap(), accounts.django);
+                assert_eq!(contract.get_participant(POOL_ID, 1000 * i + 1000).unwrap(), accounts.django);
+            }
+
+            assert_eq!(contract.get_participant(POOL_ID, 1000 * nb_iter as u128 + 1), None);
+        }
+
+        #[ink::test]
+        fn test_get_participant_after_clear_data() {
+            let mut contract = Contract::new();
+
+            let accounts = accounts();
+            for _i in 0..100 {
+                contract.add_participants(
+                    POOL_ID,
+                    1,
+                    vec![(accounts.alice, 100), (accounts.bob, 200), (accounts.charlie, 300)]
+                ).unwrap();
+            }
+            contract.clear_data(POOL_ID).unwrap();
+
+            // the index has been cleared
+            contract.add_participants(POOL_ID, 1, vec![(accounts.django, 10), (accounts.eve, 20)]).unwrap();
+            assert_eq!(contract.get_participant(POOL_ID, 10).unwrap(), accounts.django);
+            assert_eq!(contract.get_participant(POOL_ID, 11).unwrap(), accounts.eve);
+            assert_eq!(contract.get_participant(POOL_ID, 30).unwrap(), accounts.eve);
+            assert_eq!(contract.get_participant(POOL_ID, 31), None);
+            assert_eq!(contract.get_participant(POOL_ID, 1000), None);
+        }
+
+        #[ink::test]
+        fn test_update_participant() {
+            let mut contract = Contract::new_with_config(2, 10);
+
+            let accounts = accounts();
+            contract.add_participants(
+                POOL_ID,
+                1,
+                vec![(accounts.alice, 100), (accounts.bob, 200), (accounts.charlie, 300)]
+            ).unwrap();
+
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(100));
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), Some(200));
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.charlie), Some(300));
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.django), None);
+
+            contract.update_participant(POOL_ID, accounts.bob, 50).unwrap();
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), Some(50));
+            assert_eq!(contract.get_total_value(POOL_ID), 100 + 50 + 300);
+            assert_eq!(contract.get_participant(POOL_ID, 100).unwrap(), accounts.alice);
+            assert_eq!(contract.get_participant(POOL_ID, 150).unwrap(), accounts.bob);
+            assert_eq!(contract.get_participant(POOL_ID, 151).unwrap(), accounts.charlie);
+            assert_eq!(contract.get_participant(POOL_ID, 450).unwrap(), accounts.charlie);
+            assert_eq!(contract.get_participant(POOL_ID, 451), None);
+
+            contract.update_participant(POOL_ID, accounts.alice, 1000).unwrap();
+            assert_eq!(contract.get_total_value(POOL_ID), 1000 + 50 + 300);
+            assert_eq!(contract.get_participant(POOL_ID, 1000).unwrap(), accounts.alice);
+            assert_eq!(contract.get_participant(POOL_ID, 1001).unwrap(), accounts.bob);
+            assert_eq!(contract.get_nb_participants(POOL_ID), 3);
+
+            match contract.update_participant(POOL_ID, accounts.django, 10) {
+                Err(ParticipantManagerError::ParticipantNotFound) => debug_println!("Participant not found as expected"),
+                _ => panic!("Django is not a participant"),
+            }
+        }
+
+        #[ink::test]
+        fn test_remove_participant() {
+            let mut contract = Contract::new_with_config(2, 10);
+
+            let accounts = accounts();
+            contract.add_participants(
+                POOL_ID,
+                1,
+                vec![(accounts.alice, 100), (accounts.bob, 200),
+                     (accounts.charlie, 300), (accounts.django, 400), (accounts.eve, 500)]
+            ).unwrap();
+
+            // remove a participant in the first page, the last one takes its position
+            contract.remove_participant(POOL_ID, accounts.bob).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 4);
+            assert_eq!(contract.get_total_value(POOL_ID), 100 + 300 + 400 + 500);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), None);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.eve), Some(500));
+            assert_eq!(contract.get_participants(POOL_ID, 1).unwrap()[1].account, accounts.eve);
+            assert_eq!(contract.get_participants(POOL_ID, 3).unwrap().len(), 0);
+            assert_eq!(contract.get_participant(POOL_ID, 100).unwrap(), accounts.alice);
+            assert_eq!(contract.get_participant(POOL_ID, 600).unwrap(), accounts.eve);
+            assert_eq!(contract.get_participant(POOL_ID, 601).unwrap(), accounts.charlie);
+            assert_eq!(contract.get_participant(POOL_ID, 1300).unwrap(), accounts.django);
+            assert_eq!(contract.get_participant(POOL_ID, 1301), None);
+
+            // remove the last participant
+            contract.remove_participant(POOL_ID, accounts.django).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 3);
+            assert_eq!(contract.get_total_value(POOL_ID), 100 + 300 + 500);
+            assert_eq!(contract.get_participant(POOL_ID, 900).unwrap(), accounts.charlie);
+            assert_eq!(contract.get_participant(POOL_ID, 901), None);
+
+            // remove a participant in the same page as the last one
+            contract.remove_participant(POOL_ID, accounts.alice).unwrap();
+            contract.remove_participant(POOL_ID, accounts.eve).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 1);
+            assert_eq!(contract.get_participant(POOL_ID, 1).unwrap(), accounts.charlie);
+            assert_eq!(contract.get_participant(POOL_ID, 300).unwrap(), accounts.charlie);
+            assert_eq!(contract.get_participant(POOL_ID, 301), None);
+
+            contract.remove_participant(POOL_ID, accounts.charlie).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 0);
+            assert_eq!(contract.get_total_value(POOL_ID), 0);
+            assert_eq!(contract.get_participant(POOL_ID, 1), None);
+
+            match contract.remove_participant(POOL_ID, accounts.charlie) {
+                Err(ParticipantManagerError::ParticipantNotFound) => debug_println!("Participant not found as expected"),
+                _ => panic!("Charlie is not a participant anymore"),
+            }
+
+            // the participants can be added again
+            contract.add_participants(POOL_ID, 1, vec![(accounts.bob, 10)]).unwrap();
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), Some(10));
+            assert_eq!(contract.get_participant(POOL_ID, 10).unwrap(), accounts.bob);
+        }
+
+        #[ink::test]
+        fn test_duplicate_participants_allowed() {
+            let mut contract = Contract::new_with_config(2, 10);
+            assert_eq!(contract.get_duplicate_mode(), DuplicateMode::Allow);
+
+            let accounts = accounts();
+            contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.alice, 200)]).unwrap();
+            contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 300)]).unwrap();
+
+            // a new entry is added for each occurrence
+            assert_eq!(contract.get_nb_participants(POOL_ID), 3);
+            assert_eq!(contract.get_total_value(POOL_ID), 600);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(600));
+        }
+
+        #[ink::test]
+        fn test_update_duplicate_participants_allowed() {
+            let mut contract = Contract::new_with_config(2, 10);
+
+            let accounts = accounts();
+            contract.add_participants(
+                POOL_ID,
+                1,
+                vec![(accounts.alice, 100), (accounts.bob, 200), (accounts.alice, 300), (accounts.charlie, 400), (accounts.alice, 500)]
+            ).unwrap();
+
+            // all entries are replaced by one entry with the new value
+            contract.update_participant(POOL_ID, accounts.alice, 50).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 3);
+            assert_eq!(contract.get_total_value(POOL_ID), 50 + 200 + 400);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(50));
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), Some(200));
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.charlie), Some(400));
+            assert_eq!(contract.get_participant(POOL_ID, 50).unwrap(), accounts.alice);
+            assert_eq!(contract.get_participant(POOL_ID, 250).unwrap(), accounts.bob);
+            assert_eq!(contract.get_participant(POOL_ID, 650).unwrap(), accounts.charlie);
+            assert_eq!(contract.get_participant(POOL_ID, 651), None);
+        }
+
+        #[ink::test]
+        fn test_remove_duplicate_participants_allowed() {
+            let mut contract = Contract::new_with_config(2, 10);
+
+            let accounts = accounts();
+            contract.add_participants(
+                POOL_ID,
+                1,
+                vec![(accounts.alice, 100), (accounts.bob, 200), (accounts.alice, 300), (accounts.bob, 400), (accounts.charlie, 500)]
+            ).unwrap();
+            assert_eq!(contract.get_nb_accounts_for_era(POOL_ID, 1), 3);
+
+            // all entries of bob are removed
+            contract.remove_participant(POOL_ID, accounts.bob).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 3);
+            assert_eq!(contract.get_nb_accounts_for_era(POOL_ID, 1), 2);
+            assert_eq!(contract.get_total_value(POOL_ID), 100 + 300 + 500);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), None);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(400));
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.charlie), Some(500));
+
+            // the entries moved are still indexed
+            contract.remove_participant(POOL_ID, accounts.alice).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 1);
+            assert_eq!(contract.get_total_value(POOL_ID), 500);
+            assert_eq!(contract.get_participant(POOL_ID, 500).unwrap(), accounts.charlie);
+            assert_eq!(contract.get_participant(POOL_ID, 501), None);
+
+            contract.update_participant(POOL_ID, accounts.charlie, 10).unwrap();
+            assert_eq!(contract.get_total_value(POOL_ID), 10);
+            contract.remove_participant(POOL_ID, accounts.charlie).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 0);
+            assert_eq!(contract.get_nb_accounts_for_era(POOL_ID, 1), 0);
+            assert_eq!(contract.get_total_value(POOL_ID), 0);
+        }
+
+        #[ink::test]
+        fn test_merge_duplicate_participants() {
+            let mut contract = Contract::new_with_config(2, 10);
+            contract.set_duplicate_mode(DuplicateMode::Merge).unwrap();
+
+            let accounts = accounts();
+            // duplicates in the same batch
+            contract.add_participants(
+                POOL_ID,
+                1,
+                vec![(accounts.alice, 100), (accounts.bob, 200), (accounts.alice, 50), (accounts.charlie, 300)]
+            ).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 3);
+            assert_eq!(contract.get_total_value(POOL_ID), 650);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(150));
+
+            // duplicates across batches, in the last page and in a previous page
+            contract.add_participants(
+                POOL_ID,
+                1,
+                vec![(accounts.charlie, 10), (accounts.bob, 20), (accounts.django, 400)]
+            ).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 4);
+            assert_eq!(contract.get_total_value(POOL_ID), 1080);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), Some(220));
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.charlie), Some(310));
+            assert_eq!(contract.get_participants(POOL_ID, 1).unwrap().len(), 2);
+            assert_eq!(contract.get_participants(POOL_ID, 2).unwrap().len(), 2);
+
+            // the index is updated with the merged weights
+            assert_eq!(contract.get_participant(POOL_ID, 150).unwrap(), accounts.alice);
+            assert_eq!(contract.get_participant(POOL_ID, 151).unwrap(), accounts.bob);
+            assert_eq!(contract.get_participant(POOL_ID, 370).unwrap(), accounts.bob);
+            assert_eq!(contract.get_participant(POOL_ID, 680).unwrap(), accounts.charlie);
+            assert_eq!(contract.get_participant(POOL_ID, 1080).unwrap(), accounts.django);
+            assert_eq!(contract.get_participant(POOL_ID, 1081), None);
+        }
+
+        #[ink::test]
+        fn test_merge_duplicate_participants_max_size() {
+            let mut contract = Contract::new_with_config(2, 2);
+            contract.set_duplicate_mode(DuplicateMode::Merge).unwrap();
+
+            let accounts = accounts();
+            contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.bob, 200)]).unwrap();
+            // merged participants do not use new entries
+            contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.bob, 200)]).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 2);
+            assert_eq!(contract.get_total_value(POOL_ID), 600);
+
+            match contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.charlie, 300)]) {
+                Err(ParticipantManagerError::MaxSizeExceeded) => debug_println!("Max size exceeded"),
+                _ => panic!("We should exceed the max size limit"),
+            }
+        }
+
+        #[ink::test]
+        fn test_reject_duplicate_participants() {
+            let mut contract = Contract::new_with_config(2, 10);
+            contract.set_duplicate_mode(DuplicateMode::Reject).unwrap();
+
+            let accounts = accounts();
+            match contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.alice, 200)]) {
+                Err(ParticipantManagerError::DuplicateParticipant) => debug_println!("Duplicate participant as expected"),
+                _ => panic!("Alice should be rejected"),
             }
+            assert_eq!(contract.get_nb_participants(POOL_ID), 0);
 
+            contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.bob, 200)]).unwrap();
+            match contract.add_participants(POOL_ID, 1, vec![(accounts.charlie, 300), (accounts.bob, 200)]) {
+                Err(ParticipantManagerError::DuplicateParticipant) => debug_println!("Duplicate participant as expected"),
+                _ => panic!("Bob should be rejected"),
+            }
+            assert_eq!(contract.get_nb_participants(POOL_ID), 2);
+            assert_eq!(contract.get_total_value(POOL_ID), 300);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.charlie), None);
+        }
+
+        #[ink::test]
+        fn test_participants_by_era() {
+            let mut contract = Contract::new_with_config(2, 10);
+
+            let accounts = accounts();
+            contract.add_participants(
+                POOL_ID,
+                1,
+                vec![(accounts.alice, 100), (accounts.bob, 200), (accounts.charlie, 300)]
+            ).unwrap();
+            contract.add_participants(POOL_ID, 2, vec![(accounts.django, 400)]).unwrap();
+
+            // the participants of the previous era are kept
+            assert_eq!(contract.get_current_era(POOL_ID), 2);
+            assert_eq!(contract.get_eras(POOL_ID), vec![1, 2]);
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 1), 3);
+            assert_eq!(contract.get_total_value_for_era(POOL_ID, 1), 600);
+            assert_eq!(contract.get_participants_for_era(POOL_ID, 1, 1).unwrap().len(), 2);
+            assert_eq!(contract.get_participants_for_era(POOL_ID, 1, 2).unwrap().len(), 1);
+            assert_eq!(contract.get_participant_for_era(POOL_ID, 1, 600).unwrap(), accounts.charlie);
+            assert_eq!(contract.get_participant_for_era(POOL_ID, 1, 601), None);
+
+            // the getters without era return the data of the current era
+            assert_eq!(contract.get_nb_participants(POOL_ID), 1);
+            assert_eq!(contract.get_total_value(POOL_ID), 400);
+            assert_eq!(contract.get_participants(POOL_ID, 1).unwrap().len(), 1);
+            assert_eq!(contract.get_participant(POOL_ID, 400).unwrap(), accounts.django);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.django), Some(400));
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), None);
+
+            // the current era is not changed by the participants added for a previous era
+            contract.add_participants(POOL_ID, 1, vec![(accounts.eve, 500)]).unwrap();
+            assert_eq!(contract.get_current_era(POOL_ID), 2);
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 1), 4);
+            assert_eq!(contract.get_participant_for_era(POOL_ID, 1, 1100).unwrap(), accounts.eve);
+
+            // clear the current era only
+            contract.clear_data(POOL_ID).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 0);
+            assert_eq!(contract.get_eras(POOL_ID), vec![1]);
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 1), 4);
+        }
+
+        #[ink::test]
+        fn test_prune_eras() {
+            let mut contract = Contract::new_with_config(2, 10);
+
+            let accounts = accounts();
+            for era in 1..=4 {
+                contract.add_participants(POOL_ID, era, vec![(accounts.alice, 100), (accounts.bob, 200), (accounts.charlie, 300)]).unwrap();
+            }
+            assert_eq!(contract.get_eras(POOL_ID), vec![1, 2, 3, 4]);
+
+            // no era locked by a raffle
+            match contract.prune_eras(POOL_ID, 3) {
+                Err(ParticipantManagerError::EraNotPrunable) => debug_println!("EraNotPrunable as expected"),
+                _ => panic!("Error 1"),
+            }
+            assert_eq!(contract.get_eras(POOL_ID), vec![1, 2, 3, 4]);
+
+            // the raffle of the era 3 is drawn
+            contract._lock_era(POOL_ID, 3);
+            contract.prune_eras(POOL_ID, 3).unwrap();
+            assert_eq!(contract.get_eras(POOL_ID), vec![3, 4]);
+            for era in 1..=2 {
+                assert_eq!(contract.get_nb_participants_for_era(POOL_ID, era), 0);
+                assert_eq!(contract.get_total_value_for_era(POOL_ID, era), 0);
+                assert_eq!(contract.get_participants_for_era(POOL_ID, era, 1).unwrap().len(), 0);
+                assert_eq!(contract.get_participant_for_era(POOL_ID, era, 1), None);
+            }
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 3), 3);
+            assert_eq!(contract.get_participant_for_era(POOL_ID, 3, 600).unwrap(), accounts.charlie);
+
+            // the participants can't be added again in a pruned era
+            match contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 10)]) {
+                Err(ParticipantManagerError::EraLocked) => debug_println!("EraLocked as expected"),
+                _ => panic!("Error 2"),
+            }
+
+            // the locked era and the next ones are kept
+            match contract.prune_eras(POOL_ID, 4) {
+                Err(ParticipantManagerError::EraNotPrunable) => debug_println!("EraNotPrunable as expected"),
+                _ => panic!("Error 3"),
+            }
+
+            // the current era is kept even if it is locked
+            contract._lock_era(POOL_ID, 5);
+            match contract.prune_eras(POOL_ID, 5) {
+                Err(ParticipantManagerError::EraNotPrunable) => debug_println!("EraNotPrunable as expected"),
+                _ => panic!("Error 4"),
+            }
+
+            // test idempotency
+            contract.prune_eras(POOL_ID, 4).unwrap();
+            contract.prune_eras(POOL_ID, 4).unwrap();
+            assert_eq!(contract.get_eras(POOL_ID), vec![4]);
+        }
+
+        #[ink::test]
+        fn test_prune_eras_by_pages() {
+            let mut contract = Contract::new_with_config(2, 50);
+
+            // 15 pages by era
+            let participants: Vec<(AccountId, Balance)> = (1..=30u8)
+                .map(|i| (AccountId::from([i; 32]), i as Balance))
+                .collect();
+            for era in 1..=3 {
+                contract.add_participants(POOL_ID, era, participants.clone()).unwrap();
+            }
+            contract._lock_era(POOL_ID, 3);
+
+            // the first call removes 10 pages of the first era
+            contract.prune_eras(POOL_ID, 3).unwrap();
+            assert_eq!(contract.get_eras(POOL_ID), vec![1, 2, 3]);
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 1), 10);
+            assert_eq!(contract.get_participants_for_era(POOL_ID, 1, 6).unwrap().len(), 0);
+            assert_eq!(contract.get_participants_for_era(POOL_ID, 1, 5).unwrap().len(), 2);
+
+            // the second call removes the last pages of the first era and 5 pages of the second era
+            contract.prune_eras(POOL_ID, 3).unwrap();
+            assert_eq!(contract.get_eras(POOL_ID), vec![2, 3]);
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 1), 0);
+            assert_eq!(contract.get_participant_for_era(POOL_ID, 1, 1), None);
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 2), 20);
+
+            contract.prune_eras(POOL_ID, 3).unwrap();
+            assert_eq!(contract.get_eras(POOL_ID), vec![3]);
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 2), 0);
+            assert_eq!(contract.get_total_value_for_era(POOL_ID, 2), 0);
+            assert_eq!(contract.get_participant_for_era(POOL_ID, 2, 1), None);
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 3), 30);
+            assert_eq!(contract.get_participant_for_era(POOL_ID, 3, 465).unwrap(), AccountId::from([30; 32]));
+        }
+
+        #[ink::test]
+        fn test_weights_index_after_shrink() {
+            let mut contract = Contract::new_with_config(2, 16);
+
+            let participants: Vec<(AccountId, Balance)> = (1..=7u8)
+                .map(|i| (AccountId::from([i; 32]), 100))
+                .collect();
+            contract.add_participants(POOL_ID, 1, participants).unwrap();
+
+            // the count of participants shrinks below the highest position
+            for i in 4..=7u8 {
+                contract.remove_participant(POOL_ID, AccountId::from([i; 32])).unwrap();
+            }
+            assert_eq!(contract.get_nb_participants(POOL_ID), 3);
+            assert_eq!(contract.get_total_value(POOL_ID), 300);
+
+            // the nodes written by the removed participants are cleared with the era
+            contract.clear_data(POOL_ID).unwrap();
+            contract.add_participants(POOL_ID, 1, vec![(AccountId::from([8; 32]), 10)]).unwrap();
+            assert_eq!(contract.get_participant(POOL_ID, 10).unwrap(), AccountId::from([8; 32]));
+            assert_eq!(contract.get_participant(POOL_ID, 11), None);
+            assert_eq!(contract.find_participant(POOL_ID, AccountId::from([8; 32]))[0].end, 10);
+        }
+
+        #[ink::test]
+        fn test_weight_policies() {
+            let accounts = accounts();
+            let participants = vec![(accounts.alice, 10_000), (accounts.bob, 100), (accounts.charlie, 0)];
+
+            let expected = [
+                (WeightPolicy::Linear, [10_000, 100, 0]),
+                (WeightPolicy::SquareRoot, [100, 10, 0]),
+                (WeightPolicy::Cap(1_000), [1_000, 100, 0]),
+                (WeightPolicy::Logarithmic, [14, 7, 0]),
+                (WeightPolicy::Equal, [1, 1, 0]),
+            ];
+
+            for (weight_policy, weights) in expected {
+                let mut contract = Contract::new_with_config(2, 10);
+                assert_eq!(contract.get_weight_policy(), WeightPolicy::Linear);
+                contract.set_weight_policy(weight_policy).unwrap();
+                assert_eq!(contract.get_weight_policy(), weight_policy);
+
+                contract.add_participants(POOL_ID, 1, participants.clone()).unwrap();
+                assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(weights[0]));
+                assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), Some(weights[1]));
+                assert_eq!(contract.get_participant_value(POOL_ID, accounts.charlie), Some(weights[2]));
+                // the total is the sum of the transformed weights
+                assert_eq!(contract.get_total_value(POOL_ID), weights.iter().sum::<Balance>());
+                assert_eq!(contract.get_participant(POOL_ID, weights[0]).unwrap(), accounts.alice);
+                assert_eq!(contract.get_participant(POOL_ID, weights[0] + 1).unwrap(), accounts.bob);
+                assert_eq!(contract.get_participant(POOL_ID, weights[0] + weights[1] + 1), None);
+            }
+        }
+
+        #[ink::test]
+        fn test_weight_policy_on_split_values() {
+            let accounts = accounts();
+
+            // the cap can't be bypassed with many entries
+            let mut contract = Contract::new_with_config(2, 10);
+            contract.set_weight_policy(WeightPolicy::Cap(1_000)).unwrap();
+            contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 800), (accounts.bob, 100)]).unwrap();
+            contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 800), (accounts.alice, 800)]).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 4);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(1_000));
+            assert_eq!(contract.get_total_value(POOL_ID), 1_000 + 100);
+            assert_eq!(contract.get_participant(POOL_ID, 1_100).unwrap(), accounts.alice);
+            assert_eq!(contract.get_participant(POOL_ID, 1_101), None);
+
+            // the square root is applied on the sum of the merged values
+            let mut contract = Contract::new_with_config(2, 10);
+            contract.set_weight_policy(WeightPolicy::SquareRoot).unwrap();
+            contract.set_duplicate_mode(DuplicateMode::Merge).unwrap();
+            contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 50), (accounts.alice, 50)]).unwrap();
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(10));
+            contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 300)]).unwrap();
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(20));
+            assert_eq!(contract.get_total_value(POOL_ID), 20);
+
+            // the new value replaces the sum of the values
+            contract.update_participant(POOL_ID, accounts.alice, 100).unwrap();
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(10));
+            contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 21)]).unwrap();
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(11));
+            assert_eq!(contract.get_total_value(POOL_ID), 11);
+        }
+
+        #[ink::test]
+        fn test_weight_policy_on_moved_participant() {
+            let mut contract = Contract::new_with_config(2, 10);
+            contract.set_weight_policy(WeightPolicy::Cap(1_000)).unwrap();
+
+            let accounts = accounts();
+            contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.bob, 800)]).unwrap();
+
+            // bob, the last participant, takes the position of alice
+            contract.remove_participant(POOL_ID, accounts.alice).unwrap();
+            assert_eq!(contract.get_participants(POOL_ID, 1).unwrap()[0].account, accounts.bob);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), Some(800));
+
+            // the values given before the move are still counted in the cap
+            contract.add_participants(POOL_ID, 1, vec![(accounts.bob, 800)]).unwrap();
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), Some(1_000));
+            assert_eq!(contract.get_total_value(POOL_ID), 1_000);
+        }
+
+        #[ink::test]
+        fn test_weight_policy_on_update() {
+            let mut contract = Contract::new_with_config(2, 10);
+            contract.set_weight_policy(WeightPolicy::SquareRoot).unwrap();
+
+            let accounts = accounts();
+            contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 99), (accounts.bob, 1_000_000)]).unwrap();
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(9));
+            assert_eq!(contract.get_total_value(POOL_ID), 9 + 1_000);
+
+            contract.update_participant(POOL_ID, accounts.alice, 400).unwrap();
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(20));
+            assert_eq!(contract.get_total_value(POOL_ID), 20 + 1_000);
+
+            // the weights already added are not changed
+            contract.set_weight_policy(WeightPolicy::Linear).unwrap();
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), Some(1_000));
+        }
+
+        #[ink::test]
+        fn test_apply_weight_policy() {
+            assert_eq!(apply_weight_policy(&WeightPolicy::SquareRoot, u128::MAX), u64::MAX as u128);
+            assert_eq!(apply_weight_policy(&WeightPolicy::SquareRoot, 15), 3);
+            assert_eq!(apply_weight_policy(&WeightPolicy::SquareRoot, 16), 4);
+            assert_eq!(apply_weight_policy(&WeightPolicy::SquareRoot, 1), 1);
+            assert_eq!(apply_weight_policy(&WeightPolicy::Logarithmic, 1), 1);
+            assert_eq!(apply_weight_policy(&WeightPolicy::Logarithmic, u128::MAX), 128);
+            assert_eq!(apply_weight_policy(&WeightPolicy::Cap(0), 10), 0);
+        }
+
+        #[ink::test]
+        fn test_set_weight_policy_not_allowed() {
+            let mut contract = Contract::new();
+
+            let accounts = accounts();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            match contract.set_weight_policy(WeightPolicy::Equal) {
+                Err(ParticipantManagerError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Bob is not allowed to set the weight policy"),
+            }
+            assert_eq!(contract.get_weight_policy(), WeightPolicy::Linear);
+        }
+
+        #[ink::test]
+        fn test_value_thresholds_drop() {
+            let mut contract = Contract::new_with_config(2, 10);
+            assert_eq!(contract.get_value_thresholds(), (None, None));
+            assert_eq!(contract.get_threshold_mode(), ThresholdMode::Drop);
+
+            let accounts = accounts();
+            let participants = vec![
+                (accounts.alice, 1), (accounts.bob, 100), (accounts.charlie, 500),
+                (accounts.django, 1_000), (accounts.eve, 1_001)
+            ];
+
+            // no threshold
+            assert_eq!(contract.add_participants(POOL_ID, 1, participants.clone()).unwrap(), (5, 0));
+
+            // min and max values are included
+            contract.set_value_thresholds(Some(100), Some(1_000)).unwrap();
+            assert_eq!(contract.get_value_thresholds(), (Some(100), Some(1_000)));
+            assert_eq!(contract.add_participants(POOL_ID, 2, participants.clone()).unwrap(), (3, 2));
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 2), 3);
+            assert_eq!(contract.get_total_value_for_era(POOL_ID, 2), 1_600);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), None);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.eve), None);
+
+            // only a min value
+            contract.set_value_thresholds(Some(500), None).unwrap();
+            assert_eq!(contract.add_participants(POOL_ID, 3, participants.clone()).unwrap(), (3, 2));
+            assert_eq!(contract.get_total_value_for_era(POOL_ID, 3), 2_501);
+
+            // all participants are dropped
+            contract.set_value_thresholds(Some(10_000), None).unwrap();
+            assert_eq!(contract.add_participants(POOL_ID, 4, participants).unwrap(), (0, 5));
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 4), 0);
+        }
+
+        #[ink::test]
+        fn test_value_thresholds_reject() {
+            let mut contract = Contract::new_with_config(2, 10);
+            contract.set_value_thresholds(Some(100), Some(1_000)).unwrap();
+            contract.set_threshold_mode(ThresholdMode::Reject).unwrap();
+            assert_eq!(contract.get_threshold_mode(), ThresholdMode::Reject);
+
+            let accounts = accounts();
+            match contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.bob, 99)]) {
+                Err(ParticipantManagerError::ValueOutOfRange) => debug_println!("Value out of range as expected"),
+                _ => panic!("Bob should be rejected"),
+            }
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 1), 0);
+
+            assert_eq!(contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.bob, 1_000)]).unwrap(), (2, 0));
+
+            match contract.set_value_thresholds(Some(1_000), Some(100)) {
+                Err(ParticipantManagerError::InvalidThresholds) => debug_println!("Invalid thresholds as expected"),
+                _ => panic!("The min value should be lower than the max value"),
+            }
+            assert_eq!(contract.get_value_thresholds(), (Some(100), Some(1_000)));
+        }
+
+        #[ink::test]
+        fn test_value_thresholds_by_account() {
+            let mut contract = Contract::new_with_config(2, 10);
+            contract.set_value_thresholds(Some(100), Some(1_000)).unwrap();
+
+            let accounts = accounts();
+            // the thresholds are checked on the sum of the values given for the account
+            assert_eq!(contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 600), (accounts.alice, 600)]).unwrap(), (1, 1));
+            assert_eq!(contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 500)]).unwrap(), (0, 1));
+            assert_eq!(contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 400)]).unwrap(), (1, 0));
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(1_000));
+
+            contract.set_threshold_mode(ThresholdMode::Reject).unwrap();
+            match contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 1)]) {
+                Err(ParticipantManagerError::ValueOutOfRange) => debug_println!("Value out of range as expected"),
+                _ => panic!("Error 1"),
+            }
+
+            // the new value of a participant is in the thresholds
+            match contract.update_participant(POOL_ID, accounts.alice, 1_001) {
+                Err(ParticipantManagerError::ValueOutOfRange) => debug_println!("Value out of range as expected"),
+                _ => panic!("Error 2"),
+            }
+            match contract.update_participant(POOL_ID, accounts.alice, 99) {
+                Err(ParticipantManagerError::ValueOutOfRange) => debug_println!("Value out of range as expected"),
+                _ => panic!("Error 3"),
+            }
+            contract.update_participant(POOL_ID, accounts.alice, 100).unwrap();
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(100));
+            assert_eq!(contract.get_total_value(POOL_ID), 100);
+
+            // the participants of a closed pool can't be updated
+            contract._close_pool(POOL_ID).unwrap();
+            match contract.update_participant(POOL_ID, accounts.alice, 200) {
+                Err(ParticipantManagerError::PoolNotFound) => debug_println!("PoolNotFound as expected"),
+                _ => panic!("Error 4"),
+            }
+        }
+
+        #[ink::test]
+        fn test_get_participants_range() {
+            let mut contract = Contract::new_with_config(3, 20);
+
+            // participants with the values 1, 2, ... 10
+            let participants: Vec<(AccountId, Balance)> = (1..=10u8)
+                .map(|i| (AccountId::from([i; 32]), i as Balance))
+                .collect();
+            contract.add_participants(POOL_ID, 1, participants).unwrap();
+
+            let values = |participants: Vec<Participant>| -> Vec<Balance> {
+                participants.iter().map(|participant| participant.value).collect()
+            };
+
+            // across the pages
+            let (participants, nb_participants) = contract.get_participants_range(POOL_ID, 2, 5);
+            assert_eq!(nb_participants, 10);
+            assert_eq!(values(participants), vec![3, 4, 5, 6, 7]);
+
+            let (participants, _) = contract.get_participants_range(POOL_ID, 0, 3);
+            assert_eq!(values(participants), vec![1, 2, 3]);
+
+            // the end of the list
+            let (participants, _) = contract.get_participants_range(POOL_ID, 8, 100);
+            assert_eq!(values(participants), vec![9, 10]);
+
+            let (participants, nb_participants) = contract.get_participants_range(POOL_ID, 10, 100);
+            assert_eq!(participants.len(), 0);
+            assert_eq!(nb_participants, 10);
+
+            let (participants, _) = contract.get_participants_range(POOL_ID, u32::MAX, u16::MAX);
+            assert_eq!(participants.len(), 0);
+
+            let (participants, _) = contract.get_participants_range(POOL_ID, 0, 0);
+            assert_eq!(participants.len(), 0);
+
+            let (participants, _) = contract.get_participants_range(POOL_ID, 0, u16::MAX);
+            assert_eq!(values(participants), (1..=10).collect::<Vec<Balance>>());
+        }
+
+        #[ink::test]
+        fn test_find_participant() {
+            let mut contract = Contract::new_with_config(2, 10);
+
+            let accounts = accounts();
+            contract.add_participants(
+                POOL_ID,
+                1,
+                vec![(accounts.alice, 100), (accounts.bob, 200), (accounts.charlie, 300), (accounts.alice, 50)]
+            ).unwrap();
+
+            // all entries of the account are returned
+            assert_eq!(
+                contract.find_participant(POOL_ID, accounts.alice),
+                vec![
+                    ParticipantRange { account: accounts.alice, value: 100, start: 0, end: 100 },
+                    ParticipantRange { account: accounts.alice, value: 50, start: 600, end: 650 },
+                ]
+            );
+            assert_eq!(
+                contract.find_participant(POOL_ID, accounts.bob),
+                vec![ParticipantRange { account: accounts.bob, value: 200, start: 100, end: 300 }]
+            );
+            let charlie = contract.find_participant(POOL_ID, accounts.charlie)[0].clone();
+            assert_eq!((charlie.start, charlie.end), (300, 600));
+            // the participant is selected with the weights in ]start, end]
+            assert_eq!(contract.get_participant(POOL_ID, charlie.start), Some(accounts.bob));
+            assert_eq!(contract.get_participant(POOL_ID, charlie.start + 1), Some(accounts.charlie));
+            assert_eq!(contract.get_participant(POOL_ID, charlie.end), Some(accounts.charlie));
+
+            assert_eq!(contract.find_participant(POOL_ID, accounts.django), vec![]);
+
+            // the range is updated when a previous participant is updated
+            contract.update_participant(POOL_ID, accounts.bob, 10).unwrap();
+            let charlie = contract.find_participant(POOL_ID, accounts.charlie)[0].clone();
+            assert_eq!((charlie.start, charlie.end), (110, 410));
         }
 
         #[ink::test]
@@ -150,6 +1024,8 @@ pub mod participant_manager {
             let nb_iter = MAX_PART / 4;
             for _i in 0..nb_iter {
                 contract.add_participants(
+                    POOL_ID,
+                    1,
                     vec![
                         (accounts.alice, 100),
                         (accounts.bob, 200),
@@ -158,9 +1034,9 @@ pub mod participant_manager {
                     ]
                 ).unwrap();
             }
-            assert_eq!(contract.get_nb_participants() as usize, MAX_PART);
+            assert_eq!(contract.get_nb_participants(POOL_ID) as usize, MAX_PART);
 
-            match contract.add_participants(vec![(accounts.alice, 100)]) {
+            match contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 100)]) {
                 Err(ParticipantManagerError::MaxSizeExceeded) => debug_println!("Max size exceeded"),
                 _ => panic!("We should exceed the max size limit"),
             }
@@ -175,6 +1051,8 @@ pub mod participant_manager {
             let nb_iter = MAX_PART / 4;
             for _i in 0..nb_iter {
                 contract.add_participants(
+                    POOL_ID,
+                    1,
                     vec![
                         (accounts.alice, 100),
                         (accounts.bob, 200),
@@ -183,18 +1061,76 @@ pub mod participant_manager {
                     ]
                 ).unwrap();
             }
-            assert_eq!(contract.get_nb_participants() as usize, MAX_PART);
-            assert_ne!(contract.get_total_value(), 0);
+            assert_eq!(contract.get_nb_participants(POOL_ID) as usize, MAX_PART);
+            assert_ne!(contract.get_total_value(POOL_ID), 0);
 
-            contract.clear_data().unwrap();
-            assert_eq!(contract.get_nb_participants(), 0);
-            assert_eq!(contract.get_total_value(), 0);
+            contract.clear_data(POOL_ID).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 0);
+            assert_eq!(contract.get_total_value(POOL_ID), 0);
 
             // test idempotency
-            contract.clear_data().unwrap();
-            assert_eq!(contract.get_nb_participants(), 0);
-            assert_eq!(contract.get_total_value(), 0);
+            contract.clear_data(POOL_ID).unwrap();
+            assert_eq!(contract.get_nb_participants(POOL_ID), 0);
+            assert_eq!(contract.get_total_value(POOL_ID), 0);
+
+        }
+
+        #[ink::test]
+        fn test_pools() {
+            let mut contract = Contract::new();
+            let accounts = accounts();
+            let other_pool = 2;
 
+            assert_eq!(contract.get_pools(), vec![POOL_ID]);
+            match contract._create_pool(POOL_ID) {
+                Err(ParticipantManagerError::PoolAlreadyExists) => debug_println!("PoolAlreadyExists as expected"),
+                _ => panic!("Error 1"),
+            }
+            match contract.add_participants(other_pool, 1, vec![(accounts.alice, 100)]) {
+                Err(ParticipantManagerError::PoolNotFound) => debug_println!("PoolNotFound as expected"),
+                _ => panic!("Error 2"),
+            }
+
+            contract._create_pool(other_pool).unwrap();
+            assert_eq!(contract.get_pools(), vec![POOL_ID, other_pool]);
+            assert!(contract.is_pool_open(other_pool));
+
+            // the participants are stored by pool
+            contract.add_participants(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.bob, 200)]).unwrap();
+            contract.add_participants(other_pool, 3, vec![(accounts.bob, 50)]).unwrap();
+
+            assert_eq!(contract.get_current_era(POOL_ID), 1);
+            assert_eq!(contract.get_current_era(other_pool), 3);
+            assert_eq!(contract.get_nb_participants(POOL_ID), 2);
+            assert_eq!(contract.get_nb_participants(other_pool), 1);
+            assert_eq!(contract.get_total_value(POOL_ID), 300);
+            assert_eq!(contract.get_total_value(other_pool), 50);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), Some(200));
+            assert_eq!(contract.get_participant_value(other_pool, accounts.bob), Some(50));
+            assert_eq!(contract.get_participant_value(other_pool, accounts.alice), None);
+            assert_eq!(contract.get_participant(other_pool, 50), Some(accounts.bob));
+
+            // the participants of a pool are updated, removed and cleared without impact on the other pool
+            contract.update_participant(other_pool, accounts.bob, 80).unwrap();
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.bob), Some(200));
+            contract.remove_participant(POOL_ID, accounts.alice).unwrap();
+            assert_eq!(contract.get_nb_participants(other_pool), 1);
+            contract.clear_data(other_pool).unwrap();
+            assert_eq!(contract.get_nb_participants(other_pool), 0);
+            assert_eq!(contract.get_nb_participants(POOL_ID), 1);
+
+            // no participant can be added in a closed pool
+            contract._close_pool(other_pool).unwrap();
+            assert!(!contract.is_pool_open(other_pool));
+            assert_eq!(contract.get_pools(), vec![POOL_ID]);
+            match contract.add_participants(other_pool, 4, vec![(accounts.alice, 100)]) {
+                Err(ParticipantManagerError::PoolNotFound) => debug_println!("PoolNotFound as expected"),
+                _ => panic!("Error 3"),
+            }
+            match contract._close_pool(other_pool) {
+                Err(ParticipantManagerError::PoolNotFound) => debug_println!("PoolNotFound as expected"),
+                _ => panic!("Error 4"),
+            }
         }
 
     }
@@ -8,6 +8,20 @@ pub mod raffle {
         *,
         participant_manager::*,
         participant_filter::filter_latest_winners::*,
+        participant_filter::filter_registry,
+        participant_filter::filter_registry::*,
+        participant_filter::filter_denylist,
+        participant_filter::filter_denylist::*,
+        participant_filter::filter_registration,
+        participant_filter::filter_registration::*,
+        participant_filter::filter_winner_cooldown,
+        participant_filter::filter_winner_cooldown::*,
+        participant_filter::filter_winner_decay,
+        participant_filter::filter_winner_decay::*,
+        participant_filter::filter_contract_accounts,
+        participant_filter::filter_contract_accounts::*,
+        participant_filter::filter_external_registry,
+        participant_filter::filter_external_registry::*,
         reward::psp22_reward,
         reward::psp22_reward::*,
         raffle::*,
@@ -33,11 +47,40 @@ pub mod raffle {
         access: access_control::Data,
         #[storage_field]
         filter_latest_winners: filter_latest_winners::Data,
+        #[storage_field]
+        filter_registry: filter_registry::Data,
+        #[storage_field]
+        filter_denylist: filter_denylist::Data,
+        #[storage_field]
+        filter_registration: filter_registration::Data,
+        #[storage_field]
+        filter_winner_cooldown: filter_winner_cooldown::Data,
+        #[storage_field]
+        filter_winner_decay: filter_winner_decay::Data,
+        #[storage_field]
+        filter_contract_accounts: filter_contract_accounts::Data,
+        #[storage_field]
+        filter_external_registry: filter_external_registry::Data,
+        /// current era used by the filters
+        current_era: u32,
+        /// accounts eligible in the mock of the external registry
+        mock_registry: Vec<AccountId>,
+        /// number of calls to the mock of the external registry
+        nb_registry_calls: u32,
     }
 
+    pub const POOL_ID: u32 = 1;
+
     impl ParticipantManager for Contract{}
     impl Raffle for Contract{}
     impl FilterLatestWinners for Contract{}
+    impl FilterRegistry for Contract{}
+    impl FilterDenylist for Contract{}
+    impl FilterRegistration for Contract{}
+    impl FilterWinnerCooldown for Contract{}
+    impl FilterWinnerDecay for Contract{}
+    impl FilterContractAccounts for Contract{}
+    impl FilterExternalRegistry for Contract{}
     impl AccessControl for Contract{}
 
 
@@ -46,6 +89,30 @@ pub mod raffle {
             let random = RandomGenerator::get_random_number(self, min, max)?;
             Ok(random)
         }
+
+        fn get_random_numbers(&mut self, count: u32, min: u128, max: u128) -> Result<Vec<u128>, RandomError> {
+            let randoms = RandomGenerator::get_random_numbers(self, count, min, max)?;
+            Ok(randoms)
+        }
+
+        fn get_random_numbers_with_secret(&mut self, era: u32, secret: [u8; 32], count: u32, min: u128, max: u128) -> Result<Vec<u128>, RandomError> {
+            let randoms = RandomGenerator::get_random_numbers_with_secret(self, era, secret, count, min, max)?;
+            Ok(randoms)
+        }
+
+        fn commit_secret(&mut self, era: u32, commitment: Hash) -> Result<(), RandomError> {
+            RandomGenerator::commit_secret(self, era, commitment)?;
+            Ok(())
+        }
+
+        fn request_random_seed(&mut self, era: u32, secret: Option<[u8; 32]>) -> Result<u32, RandomError> {
+            let request_id = RandomGenerator::request_randomness(self, era, secret)?;
+            Ok(request_id)
+        }
+
+        fn get_random_seed(&mut self, request_id: u32) -> Result<[u8; 32], RandomError> {
+            RandomGenerator::get_randomness(self, request_id).ok_or(RandomError::RandomnessNotFulfilled)
+        }
     }
 
     impl Contract {
@@ -54,44 +121,47 @@ pub mod raffle {
             let mut instance = Self::default();
             let caller = instance.env().caller();
             instance._init_with_admin(caller);
+            instance._init_participant_manager(300, 1800).expect("Should init the participant manager");
             instance.grant_role(PARTICIPANT_MANAGER, caller).expect("Should grant the role PARTICIPANT_MANAGER");
             instance.grant_role(RAFFLE_MANAGER, caller).expect("Should grant the role RAFFLE_MANAGER");
             instance.grant_role(REWARD_MANAGER, caller).expect("Should grant the role REWARD_MANAGER");
             instance.grant_role(REWARD_VIEWER, caller).expect("Should grant the role REWARD_VIEWER");
             instance.grant_role(RANDOM_GENERATOR_CONSUMER, caller).expect("Should grant the role RANDOM_GENERATOR_CONSUMER");
+            instance.grant_role(RANDOM_GENERATOR_MANAGER, caller).expect("Should grant the role RANDOM_GENERATOR_MANAGER");
+            instance.grant_role(RANDOM_GENERATOR_ORACLE, caller).expect("Should grant the role RANDOM_GENERATOR_ORACLE");
             instance.grant_role(PARTICIPANT_FILTER_MANAGER, caller).expect("Should grant the role PARTICIPANT_FILTER_MANAGER");
+            instance._create_pool(POOL_ID).expect("Should create the pool");
             instance
         }
 
         #[ink(message)]
-        pub fn add_participants_with_filters(&mut self, participants: Vec<(AccountId, Balance)>) -> Result<(), ContractError>{
-
-            let mut parts = participants.clone();
-
-            let mut i = 0;
-            while i < parts.len() {
-                let p_account_id = parts.get(i).unwrap().0;
-                if self._is_in_last_winners(&p_account_id) {
-                    parts.remove(i);
-                } else {
-                    i += 1;
-                }
+        #[openbrush::modifiers(access_control::only_role(PARTICIPANT_MANAGER))]
+        pub fn add_participants_with_filters(&mut self, pool_id: u32, era: u32, participants: Vec<(AccountId, Balance)>) -> Result<(), ContractError>{
+
+            // the thresholds are checked on the values given, before the weights are changed by the filters
+            let (participants, nb_dropped) = self._check_thresholds(pool_id, era, participants)?;
+
+            if self.get_filters(pool_id).contains(&FilterId::ExternalRegistry) {
+                self._refresh_eligibility(pool_id, era, &participants)?;
             }
 
-            self.add_participants(parts)?;
+            let (parts, _) = self._apply_filters(pool_id, era, participants);
+
+            self._add_participants(pool_id, era, parts, nb_dropped)?;
             Ok(())
         }
 
         #[ink(message)]
-        pub fn run_raffle(&mut self, era: u32, rewards: Balance) -> Result<(), ContractError> {
+        pub fn run_raffle(&mut self, pool_id: u32, era: u32, rewards: Balance) -> Result<(), ContractError> {
 
             // select the winners
-            let winners = self._run_raffle(era, rewards)?;
+            let winners = self._run_raffle(pool_id, era, rewards, None)?;
 
 
             // save the winners
             for winner in &winners {
-                self._add_winner(winner.0);
+                self._add_winner(pool_id, winner.0);
+                self._set_last_win_era(pool_id, winner.0, era);
             }
 
             // transfer the rewards and the winners
@@ -105,12 +175,21 @@ pub mod raffle {
     #[derive(Debug, Eq, PartialEq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub enum ContractError {
+        AccessControlError(AccessControlError),
         RaffleError(RaffleError),
         RewardError(RewardError),
         ParticipantManagerError(ParticipantManagerError),
+        ParticipantFilterError(ParticipantFilterError),
     }
 
 
+    /// convertor from AccessControlError to ContractError
+    impl From<AccessControlError> for ContractError {
+        fn from(error: AccessControlError) -> Self {
+            ContractError::AccessControlError(error)
+        }
+    }
+
     /// convertor from RaffleError to ContractError
     impl From<RaffleError> for ContractError {
         fn from(error: RaffleError) -> Self {
@@ -132,6 +211,92 @@ pub mod raffle {
         }
     }
 
+    /// convertor from ParticipantFilterError to ContractError
+    impl From<ParticipantFilterError> for ContractError {
+        fn from(error: ParticipantFilterError) -> Self {
+            ContractError::ParticipantFilterError(error)
+        }
+    }
+
+
+    impl random_generator::Internal for Contract {
+        fn _emit_oracle_public_key_set_event(&self, _public_key: [u8; 33]){
+            // no event for the tests
+        }
+        fn _emit_randomness_verified_event(&self, _request_id: u32, _era: u32, _randomness: [u8; 32]){
+            // no event for the tests
+        }
+    }
+
+    impl filter_winner_cooldown::Internal for Contract {
+        fn _get_current_era(&self) -> u32 {
+            self.current_era
+        }
+    }
+
+    impl filter_external_registry::Internal for Contract {
+        fn _call_eligibility_registry(&mut self, _registry: &EligibilityRegistry, account: AccountId) -> Result<bool, ParticipantFilterError> {
+            // mock of the external registry
+            self.nb_registry_calls += 1;
+            Ok(self.mock_registry.contains(&account))
+        }
+    }
+
+    impl filter_contract_accounts::Internal for Contract {
+        fn _is_contract(&self, account: &AccountId) -> bool {
+            self.env().is_contract(account)
+        }
+    }
+
+    impl filter_registry::Internal for Contract {
+        fn _get_participant_filter(&self, filter: FilterId) -> Box<dyn ParticipantFilter + '_> {
+            match filter {
+                FilterId::LatestWinners => Box::new(&self.filter_latest_winners),
+                FilterId::Denylist => Box::new(&self.filter_denylist),
+                FilterId::Registration => Box::new(&self.filter_registration),
+                FilterId::WinnerCooldown => Box::new(&self.filter_winner_cooldown),
+                FilterId::WinnerDecay => Box::new(WinnerDecayFilter(self)),
+                FilterId::ContractAccounts => Box::new(ContractAccountsFilter(self)),
+                FilterId::ExternalRegistry => Box::new(&self.filter_external_registry),
+            }
+        }
+    }
+
+    impl filter_registration::Internal for Contract {
+        fn _get_current_era(&self) -> u32 {
+            self.current_era
+        }
+        fn _emit_registered_event(&self, _account: AccountId, _era: u32){
+            // no event for the tests
+        }
+        fn _emit_unregistered_event(&self, _account: AccountId){
+            // no event for the tests
+        }
+    }
+
+    impl filter_denylist::Internal for Contract {
+        fn _emit_added_to_denylist_event(&self, _account: AccountId){
+            // no event for the tests
+        }
+        fn _emit_removed_from_denylist_event(&self, _account: AccountId){
+            // no event for the tests
+        }
+    }
+
+    impl participant_manager::Internal for Contract {
+        fn _emit_participants_added_event(&self, _pool_id: u32, _era: u32, _nb_accepted: u32, _nb_rejected: u32, _added_value: Balance, _total_value: Balance){
+            // no event for the tests
+        }
+        fn _emit_participant_updated_event(&self, _pool_id: u32, _era: u32, _account: AccountId, _old_value: Balance, _new_value: Balance){
+            // no event for the tests
+        }
+        fn _emit_participant_removed_event(&self, _pool_id: u32, _era: u32, _account: AccountId, _value: Balance){
+            // no event for the tests
+        }
+        fn _emit_data_cleared_event(&self, _pool_id: u32, _era: u32){
+            // no event for the tests
+        }
+    }
 
     impl psp22_reward::Internal for Contract {
         fn _emit_rewards_claimed_event(&self, _account: AccountId, _amount: Balance){
@@ -145,6 +310,7 @@ pub mod raffle {
     mod tests {
         use ink::env::debug_println;
         use openbrush::test_utils::accounts;
+        use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
 
         use super::*;
 
@@ -154,21 +320,21 @@ pub mod raffle {
             let mut contract = super::Contract::new();
 
             // 50 + 30 + 20 > 80 => Error
-            let result = contract.set_ratio_distribution(vec![50, 30, 20], 90);
+            let result = contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 90);
             match result {
                 Err(IncorrectRatio) => debug_println!("Incorrect Ratio as expected"),
                 _ => panic!("Error 1"),
             };
 
             // 50 + 30 + 20 = 100 => Ok
-            let result = contract.set_ratio_distribution(vec![50, 30, 20], 100);
+            let result = contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 100);
             match result {
                 Ok(_) => debug_println!("No Error as expected"),
                 _ => panic!("Error 2"),
             };
 
             // 50 + 30 + 20 < 150 => Ok
-            let result = contract.set_ratio_distribution(vec![50, 30, 20], 150);
+            let result = contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 150);
             match result {
                 Ok(_) => debug_println!("No Error as expected"),
                 _ => panic!("Error 2"),
@@ -180,16 +346,16 @@ pub mod raffle {
         fn test_run_raffle_no_ratio_set() {
             let mut contract = super::Contract::new();
 
-            //contract.set_ratio_distribution(vec![50, 30, 20], 100).unwrap();
+            //contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 100).unwrap();
 
             let accounts = accounts();
             let participants = vec![
                 (accounts.alice, 100000), (accounts.bob, 100000), (accounts.charlie, 100000), 
                 (accounts.django, 100000), (accounts.eve, 100000), (accounts.frank, 100000)
                 ];
-            contract.add_participants_with_filters(participants).unwrap();
+            contract.add_participants_with_filters(POOL_ID, 1, participants).unwrap();
 
-            let result = contract._run_raffle(1, 1000);
+            let result = contract._run_raffle(POOL_ID, 1, 1000, None);
             match result {
                 Err(NoRatioSet) => debug_println!("NoRatioSet as expected"),
                 _ => panic!("Error 1"),
@@ -200,12 +366,12 @@ pub mod raffle {
         fn test_run_raffle_no_participant() {
             let mut contract = super::Contract::new();
 
-            contract.set_ratio_distribution(vec![50, 30, 20], 100).unwrap();
+            contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 100).unwrap();
 
             let participants = vec![];
-            contract.add_participants_with_filters(participants).unwrap();
+            contract.add_participants_with_filters(POOL_ID, 1, participants).unwrap();
 
-            let result = contract._run_raffle(1, 1000);
+            let result = contract._run_raffle(POOL_ID, 1, 1000, None);
             match result {
                 Err(NoParticipant) => debug_println!("NoParticipant as expected"),
                 _ => panic!("Error 1"),
@@ -217,16 +383,16 @@ pub mod raffle {
         fn test_run_raffle_no_reward() {
             let mut contract = super::Contract::new();
 
-            contract.set_ratio_distribution(vec![50, 30, 20], 100).unwrap();
+            contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 100).unwrap();
 
             let accounts = accounts();
             let participants = vec![
                 (accounts.alice, 100000), (accounts.bob, 100000), (accounts.charlie, 100000), 
                 (accounts.django, 100000), (accounts.eve, 100000), (accounts.frank, 100000)
                 ];
-            contract.add_participants_with_filters(participants).unwrap();
+            contract.add_participants_with_filters(POOL_ID, 1, participants).unwrap();
 
-            let result = contract._run_raffle(1, 0);
+            let result = contract._run_raffle(POOL_ID, 1, 0, None);
             match result {
                 Err(RaffleError::NoReward) => debug_println!("NoParticipant as expected"),
                 _ => panic!("Error 1"),
@@ -242,13 +408,13 @@ pub mod raffle {
                 (accounts.alice, 100000), (accounts.bob, 100000), (accounts.charlie, 100000), 
                 (accounts.django, 100000), (accounts.eve, 100000), (accounts.frank, 100000)
                 ];
-            contract.add_participants_with_filters(participants).unwrap();
+            contract.add_participants_with_filters(POOL_ID, 1, participants).unwrap();
 
             // second winner receive nada
-            contract.set_ratio_distribution(vec![50, 0, 50], 100).unwrap();
+            contract.set_ratio_distribution(POOL_ID, vec![50, 0, 50], 100).unwrap();
 
             // select the participants
-            let winners = contract._run_raffle(1, 1000).unwrap();
+            let winners = contract._run_raffle(POOL_ID, 1, 1000, None).unwrap();
 
             // assert two differents winners
             assert_eq!(winners.len(), 2); 
@@ -267,38 +433,106 @@ pub mod raffle {
 
             let mut contract = super::Contract::new();
             
-            contract.set_ratio_distribution(vec![100], 100).unwrap();
+            contract.set_ratio_distribution(POOL_ID, vec![100], 100).unwrap();
 
             let accounts = accounts();
             let rewards = 1000;
 
             // first raffle => success
             let participants = vec![(accounts.alice, 100000)];
-            contract.add_participants_with_filters(participants).unwrap();
-            contract._run_raffle(2, rewards).unwrap();
+            contract.add_participants_with_filters(POOL_ID, 2, participants).unwrap();
+            contract._run_raffle(POOL_ID, 2, rewards, None).unwrap();
 
-            // second raffle for the same era => failure
+            // the participants of an era already drawn can't be changed
             let participants = vec![(accounts.alice, 100000)];
-            contract.add_participants_with_filters(participants).unwrap();
-            let result = contract._run_raffle(2, rewards);
+            match contract.add_participants_with_filters(POOL_ID, 2, participants) {
+                Err(ContractError::ParticipantManagerError(ParticipantManagerError::EraLocked)) => debug_println!("EraLocked as expected"),
+                _ => panic!("Error 0"),
+            };
+
+            // second raffle for the same era => failure
+            let result = contract._run_raffle(POOL_ID, 2, rewards, None);
             match result {
                 Err(RaffleError::RaffleAlreadyDone) => debug_println!("RaffleAlreadyDone as expected"),
                 _ => panic!("Error 1"),
             };
 
             // second raffle for previous era => failure
-            let result = contract._run_raffle(1,  rewards);
+            let result = contract._run_raffle(POOL_ID, 1,  rewards, None);
             match result {
                 Err(RaffleError::RaffleAlreadyDone) => debug_println!("RaffleAlreadyDone as expected"),
                 _ => panic!("Error 2"),
             };
 
             // raffle for next era => success
-            contract._run_raffle(3, rewards).unwrap();
+            let participants = vec![(accounts.alice, 100000)];
+            contract.add_participants_with_filters(POOL_ID, 3, participants).unwrap();
+            contract._run_raffle(POOL_ID, 3, rewards, None).unwrap();
 
         }
 
 
+        #[ink::test]
+        fn test_run_raffle_with_era_snapshot() {
+
+            let mut contract = super::Contract::new();
+            contract.set_ratio_distribution(POOL_ID, vec![100], 100).unwrap();
+
+            let accounts = accounts();
+            contract.add_participants_with_filters(POOL_ID, 1, vec![(accounts.alice, 100000)]).unwrap();
+            // the participants of the next era are loaded before the raffle
+            contract.add_participants_with_filters(POOL_ID, 2, vec![(accounts.bob, 100000)]).unwrap();
+
+            // the raffle uses the participants of its era
+            let winners = contract._run_raffle(POOL_ID, 1, 1000, None).unwrap();
+            assert_eq!(winners, vec![(accounts.alice, 1000)]);
+
+            let winners = contract._run_raffle(POOL_ID, 2, 1000, None).unwrap();
+            assert_eq!(winners, vec![(accounts.bob, 1000)]);
+
+            // the participants of the past raffles are kept
+            assert_eq!(contract.get_participants_for_era(POOL_ID, 1, 1).unwrap().len(), 1);
+            assert_eq!(contract.get_total_value_for_era(POOL_ID, 1), 100000);
+
+            // no participant for the next era
+            match contract._run_raffle(POOL_ID, 3, 1000, None) {
+                Err(RaffleError::NoParticipant) => debug_println!("NoParticipant as expected"),
+                _ => panic!("Error 1"),
+            };
+        }
+
+        #[ink::test]
+        fn test_draw_winning_offsets() {
+
+            let mut contract = super::Contract::new();
+            contract.set_ratio_distribution(POOL_ID, vec![50, 0, 30, 20], 100).unwrap();
+
+            // only the total weight is known
+            let total_value = 1_000_000_000;
+            let offsets = contract._draw_winning_offsets(POOL_ID, 1, total_value, 1000, None).unwrap();
+
+            // no offset for the ratio equals to zero
+            assert_eq!(offsets.len(), 3);
+            let mut total_rewards = 0;
+            for (offset, reward) in &offsets {
+                assert!(*offset >= 1);
+                assert!(*offset <= total_value);
+                total_rewards += reward;
+            }
+            assert_eq!(total_rewards, 1000);
+            assert_eq!(contract.get_last_era_done(POOL_ID), 1);
+
+            match contract._draw_winning_offsets(POOL_ID, 1, total_value, 1000, None) {
+                Err(RaffleError::RaffleAlreadyDone) => debug_println!("RaffleAlreadyDone as expected"),
+                _ => panic!("Error 1"),
+            };
+
+            match contract._draw_winning_offsets(POOL_ID, 2, 0, 1000, None) {
+                Err(RaffleError::NoParticipant) => debug_println!("NoParticipant as expected"),
+                _ => panic!("Error 2"),
+            };
+        }
+
         #[ink::test]
         fn test_run_raffle_share_full_rewards() {
             let mut contract = super::Contract::new();
@@ -310,12 +544,12 @@ pub mod raffle {
                 (accounts.alice, 100000), (accounts.bob, 100000), (accounts.charlie, 100000), 
                 (accounts.django, 100000), (accounts.eve, 100000), (accounts.frank, 100000)
                 ];
-            contract.add_participants_with_filters(participants).unwrap();
+            contract.add_participants_with_filters(POOL_ID, 1, participants).unwrap();
 
-            contract.set_ratio_distribution(vec![50, 30, 20], 100).unwrap();
+            contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 100).unwrap();
 
             // select the participants
-            let winners = contract._run_raffle(1, rewards).unwrap();
+            let winners = contract._run_raffle(POOL_ID, 1, rewards, None).unwrap();
 
             // assert three different winners
             assert_eq!(winners.len(), 3); 
@@ -341,12 +575,12 @@ pub mod raffle {
                 (accounts.alice, 100000), (accounts.bob, 100000), (accounts.charlie, 100000), 
                 (accounts.django, 100000), (accounts.eve, 100000), (accounts.frank, 100000)
                 ];
-            contract.add_participants_with_filters(participants).unwrap();
+            contract.add_participants_with_filters(POOL_ID, 1, participants).unwrap();
 
-            contract.set_ratio_distribution(vec![50, 30, 20], 200).unwrap();
+            contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 200).unwrap();
 
             // select the participants
-            let winners = contract._run_raffle(1, 1000).unwrap();
+            let winners = contract._run_raffle(POOL_ID, 1, 1000, None).unwrap();
 
             // assert three different winners
             assert_eq!(winners.len(), 3);
@@ -367,17 +601,17 @@ pub mod raffle {
         fn test_raffle_contract()  {
 
             let mut contract = super::Contract::new();
-            contract.set_ratio_distribution(vec![50, 30, 20], 100).unwrap();
+            contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 100).unwrap();
 
             let era = 1;
             let accounts = accounts();
 
-            contract.add_participants_with_filters(
+            contract.add_participants_with_filters(POOL_ID, era,
                 vec![(accounts.alice, 100000), (accounts.bob, 100000), (accounts.charlie, 100000), 
                 (accounts.django, 100000), (accounts.eve, 100000), (accounts.frank, 100000)]
             ).unwrap();
 
-            contract.run_raffle(era, 1000).unwrap();
+            contract.run_raffle(POOL_ID, era, 1000).unwrap();
 
             let mut nb_winners = 0;
             let mut total_rewards = 0;
@@ -417,16 +651,16 @@ pub mod raffle {
         fn test_add_participants_with_filters()  {
 
             let mut contract = super::Contract::new();
-            contract.set_ratio_distribution(vec![50], 100).unwrap();
-            contract.set_nb_winners_filtered(2).unwrap();
+            contract.set_ratio_distribution(POOL_ID, vec![50], 100).unwrap();
+            contract.set_nb_winners_filtered(POOL_ID, 2).unwrap();
 
             let accounts = accounts();
 
-            contract.add_participants_with_filters(
+            contract.add_participants_with_filters(POOL_ID, 1,
                 vec![(accounts.alice, 100000)]
             ).unwrap();
 
-            contract.run_raffle(1, 1000).unwrap();
+            contract.run_raffle(POOL_ID, 1, 1000).unwrap();
 
             match get_reward(&mut contract, accounts.alice) {
                 Some(r) => assert_eq!(500, r),
@@ -443,13 +677,11 @@ pub mod raffle {
 
             // second era with only Alice
             // Alice already won so it should be removed from the participants
-            contract.clear_data().unwrap();
-
-            contract.add_participants_with_filters(
+            contract.add_participants_with_filters(POOL_ID, 2,
                 vec![(accounts.alice, 100000)]
             ).unwrap();
 
-            let result = contract._run_raffle(2, 1000);
+            let result = contract._run_raffle(POOL_ID, 2, 1000, None);
             match result {
                 Err(RaffleError::NoParticipant) => debug_println!("NoParticipant as expected"),
                 _ => panic!("NoParticipant is expected"),
@@ -457,11 +689,11 @@ pub mod raffle {
 
             // third era with Alice and Bob
             // Alice already won so Bob must win
-            contract.add_participants_with_filters(
+            contract.add_participants_with_filters(POOL_ID, 3,
                 vec![(accounts.alice, 100000), (accounts.bob, 1)]
             ).unwrap();
 
-            contract.run_raffle(3, 1000).unwrap();
+            contract.run_raffle(POOL_ID, 3, 1000).unwrap();
 
             match get_reward(&mut contract, accounts.bob) {
                 Some(r) => assert_eq!(500, r),
@@ -478,17 +710,785 @@ pub mod raffle {
 
             // 4th era with Alice and Bob
             // Both already won so it should be removed from the participants
-            contract.clear_data().unwrap();
+            contract.add_participants_with_filters(POOL_ID, 4,
+                vec![(accounts.alice, 100000), (accounts.bob, 1)]
+            ).unwrap();
+
+            let result = contract._run_raffle(POOL_ID, 4, 1000, None);
+            match result {
+                Err(RaffleError::NoParticipant) => debug_println!("NoParticipant as expected"),
+                _ => panic!("NoParticipant is expected"),
+            };
+        }
+
+        #[ink::test]
+        fn test_add_participants_registered_only()  {
+
+            let mut contract = super::Contract::new();
+            contract.set_ratio_distribution(POOL_ID, vec![50], 100).unwrap();
+            contract.enable_filter(POOL_ID, FilterId::Registration).unwrap();
+
+            let accounts = accounts();
 
-            contract.add_participants_with_filters(
+            // Bob registers during the era 1
+            contract.current_era = 1;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.register().unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+
+            // the registration only counts from the next era
+            contract.add_participants_with_filters(POOL_ID, 1,
                 vec![(accounts.alice, 100000), (accounts.bob, 1)]
             ).unwrap();
 
-            let result = contract._run_raffle(4, 1000);
+            let result = contract._run_raffle(POOL_ID, 1, 1000, None);
+            match result {
+                Err(RaffleError::NoParticipant) => debug_println!("NoParticipant as expected"),
+                _ => panic!("NoParticipant is expected"),
+            };
+
+            // Alice is not registered so Bob must win
+            contract.add_participants_with_filters(POOL_ID, 2,
+                vec![(accounts.alice, 100000), (accounts.bob, 1)]
+            ).unwrap();
+
+            contract.run_raffle(POOL_ID, 2, 1000).unwrap();
+
+            match get_reward(&mut contract, accounts.alice) {
+                Some(_) => panic!("Alice should not have rewards"),
+                _ => debug_println!("It's ok, Alice has no reward"),
+            };
+            match get_reward(&mut contract, accounts.bob) {
+                Some(r) => assert_eq!(500, r),
+                _ => panic!("Bob should have rewards"),
+            };
+        }
+
+        #[ink::test]
+        fn test_add_participants_with_winner_cooldown()  {
+
+            let mut contract = super::Contract::new();
+            contract.set_ratio_distribution(POOL_ID, vec![50], 100).unwrap();
+            contract.set_nb_cooldown_eras(POOL_ID, 1).unwrap();
+            contract.enable_filter(POOL_ID, FilterId::WinnerCooldown).unwrap();
+
+            let accounts = accounts();
+
+            contract.add_participants_with_filters(POOL_ID, 1,
+                vec![(accounts.alice, 100000)]
+            ).unwrap();
+            contract.run_raffle(POOL_ID, 1, 1000).unwrap();
+            assert_eq!(contract.get_last_win_era(POOL_ID, accounts.alice), Some(1));
+
+            // Alice won during the era 1 so she is excluded in the era 2
+            contract.add_participants_with_filters(POOL_ID, 2,
+                vec![(accounts.alice, 100000)]
+            ).unwrap();
+
+            let result = contract._run_raffle(POOL_ID, 2, 1000, None);
             match result {
                 Err(RaffleError::NoParticipant) => debug_println!("NoParticipant as expected"),
                 _ => panic!("NoParticipant is expected"),
             };
+
+            // the cooldown is over in the era 3
+            contract.add_participants_with_filters(POOL_ID, 3,
+                vec![(accounts.alice, 100000)]
+            ).unwrap();
+            contract.run_raffle(POOL_ID, 3, 1000).unwrap();
+            assert_eq!(contract.get_last_win_era(POOL_ID, accounts.alice), Some(3));
+        }
+
+        #[ink::test]
+        fn test_add_participants_with_winner_decay()  {
+
+            let mut contract = super::Contract::new();
+            contract.set_ratio_distribution(POOL_ID, vec![50], 100).unwrap();
+            contract.set_decay_schedule(POOL_ID, vec![50, 75]).unwrap();
+            contract.enable_filter(POOL_ID, FilterId::WinnerDecay).unwrap();
+
+            let accounts = accounts();
+
+            contract.add_participants_with_filters(POOL_ID, 1,
+                vec![(accounts.alice, 100000)]
+            ).unwrap();
+            contract.run_raffle(POOL_ID, 1, 1000).unwrap();
+
+            // Alice won during the era 1 so she keeps 50% of her weight in the era 2
+            contract.add_participants_with_filters(POOL_ID, 2,
+                vec![(accounts.alice, 100000), (accounts.bob, 100000)]
+            ).unwrap();
+            assert_eq!(contract.get_total_value_for_era(POOL_ID, 2), 150000);
+
+            // and 75% in the era 3
+            contract.add_participants_with_filters(POOL_ID, 3,
+                vec![(accounts.alice, 100000), (accounts.bob, 100000)]
+            ).unwrap();
+            assert_eq!(contract.get_total_value_for_era(POOL_ID, 3), 175000);
+
+            // the full weight is restored in the era 4
+            contract.add_participants_with_filters(POOL_ID, 4,
+                vec![(accounts.alice, 100000), (accounts.bob, 100000)]
+            ).unwrap();
+            assert_eq!(contract.get_total_value_for_era(POOL_ID, 4), 200000);
+        }
+
+        #[ink::test]
+        fn test_value_thresholds_with_winner_decay()  {
+
+            let mut contract = super::Contract::new();
+            contract.set_ratio_distribution(POOL_ID, vec![50], 100).unwrap();
+            contract.set_decay_schedule(POOL_ID, vec![50]).unwrap();
+            contract.enable_filter(POOL_ID, FilterId::WinnerDecay).unwrap();
+            contract.set_value_thresholds(Some(60000), None).unwrap();
+
+            let accounts = accounts();
+
+            contract.add_participants_with_filters(POOL_ID, 1,
+                vec![(accounts.alice, 100000)]
+            ).unwrap();
+            contract.run_raffle(POOL_ID, 1, 1000).unwrap();
+
+            // the thresholds are checked on the values given: Alice keeps 50% of her weight even if it is below the min value
+            contract.add_participants_with_filters(POOL_ID, 2,
+                vec![(accounts.alice, 100000), (accounts.bob, 50000)]
+            ).unwrap();
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 2), 1);
+            assert_eq!(contract.get_total_value_for_era(POOL_ID, 2), 50000);
+            assert_eq!(contract.get_participant_value(POOL_ID, accounts.alice), Some(50000));
+        }
+
+        #[ink::test]
+        fn test_add_participants_without_contracts()  {
+
+            let mut contract = super::Contract::new();
+            contract.enable_filter(POOL_ID, FilterId::ContractAccounts).unwrap();
+
+            let accounts = accounts();
+
+            // charlie and django are smart contracts but django is allowed
+            ink::env::test::set_contract::<ink::env::DefaultEnvironment>(accounts.charlie);
+            ink::env::test::set_contract::<ink::env::DefaultEnvironment>(accounts.django);
+            contract.add_to_contract_allowlist(vec![accounts.django]).unwrap();
+
+            contract.add_participants_with_filters(POOL_ID, 1,
+                vec![(accounts.alice, 100), (accounts.charlie, 1000), (accounts.django, 10)]
+            ).unwrap();
+
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 1), 2);
+            assert_eq!(contract.get_total_value_for_era(POOL_ID, 1), 110);
+        }
+
+        #[ink::test]
+        fn test_add_participants_with_external_registry()  {
+
+            let mut contract = super::Contract::new();
+            let accounts = accounts();
+
+            contract.enable_filter(POOL_ID, FilterId::ExternalRegistry).unwrap();
+
+            match contract.add_participants_with_filters(POOL_ID, 1, vec![(accounts.alice, 100)]) {
+                Err(ContractError::ParticipantFilterError(ParticipantFilterError::EligibilityRegistryMissing)) => debug_println!("EligibilityRegistryMissing as expected"),
+                _ => panic!("EligibilityRegistryMissing is expected"),
+            }
+
+            // only bob holds the badge
+            contract.set_eligibility_registry(POOL_ID, accounts.frank, [0, 0, 0, 1], RegistryKind::IsEligible).unwrap();
+            contract.mock_registry = vec![accounts.bob];
+
+            contract.add_participants_with_filters(POOL_ID, 1,
+                vec![(accounts.alice, 100), (accounts.bob, 10)]
+            ).unwrap();
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 1), 1);
+            assert_eq!(contract.get_total_value_for_era(POOL_ID, 1), 10);
+
+            // the results are cached for the era
+            contract.add_participants_with_filters(POOL_ID, 1,
+                vec![(accounts.alice, 100), (accounts.bob, 10), (accounts.charlie, 1)]
+            ).unwrap();
+            assert_eq!(contract.nb_registry_calls, 3);
+
+            // the registry is not called before the role of the caller is checked
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            match contract.add_participants_with_filters(POOL_ID, 1, vec![(accounts.django, 100)]) {
+                Err(ContractError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("MissingRole is expected"),
+            }
+            assert_eq!(contract.nb_registry_calls, 3);
+        }
+
+        #[ink::test]
+        fn test_run_raffle_by_pool()  {
+
+            let mut contract = super::Contract::new();
+            let daily_pool = 2;
+            contract._create_pool(daily_pool).unwrap();
+
+            // each pool has its own ratio distribution and winners filter
+            contract.set_ratio_distribution(POOL_ID, vec![50], 100).unwrap();
+            contract.set_ratio_distribution(daily_pool, vec![10], 100).unwrap();
+            contract.set_nb_winners_filtered(POOL_ID, 1).unwrap();
+            assert_eq!(contract.get_ratio_distribution(daily_pool), vec![10]);
+            assert_eq!(contract.get_nb_winners_filtered(daily_pool), 0);
+
+            let accounts = accounts();
+            contract.add_participants_with_filters(POOL_ID, 1, vec![(accounts.alice, 100000)]).unwrap();
+            contract.add_participants_with_filters(daily_pool, 1, vec![(accounts.bob, 100000)]).unwrap();
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 1), 1);
+            assert_eq!(contract.get_nb_participants_for_era(daily_pool, 1), 1);
+
+            contract.run_raffle(POOL_ID, 1, 1000).unwrap();
+            assert_eq!(get_reward(&mut contract, accounts.alice), Some(500));
+            assert_eq!(contract.get_last_era_done(POOL_ID), 1);
+            assert_eq!(contract.get_last_era_done(daily_pool), 0);
+            assert_eq!(contract.get_last_winners(POOL_ID), vec![accounts.alice]);
+            assert_eq!(contract.get_last_winners(daily_pool).len(), 0);
+
+            // the raffle of the other pool can still be run for the same era
+            contract.run_raffle(daily_pool, 1, 1000).unwrap();
+            assert_eq!(get_reward(&mut contract, accounts.bob), Some(100));
+            assert_eq!(contract.get_last_era_done(daily_pool), 1);
+
+            // alice is only filtered in the pool she won
+            contract.add_participants_with_filters(POOL_ID, 2, vec![(accounts.alice, 100000)]).unwrap();
+            contract.add_participants_with_filters(daily_pool, 2, vec![(accounts.alice, 100000)]).unwrap();
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 2), 0);
+            assert_eq!(contract.get_nb_participants_for_era(daily_pool, 2), 1);
+
+            // no raffle can be run in a closed pool
+            contract._close_pool(daily_pool).unwrap();
+            match contract._run_raffle(daily_pool, 2, 1000, None) {
+                Err(RaffleError::PoolNotFound) => debug_println!("PoolNotFound as expected"),
+                _ => panic!("PoolNotFound is expected"),
+            };
+
+            // no raffle can be run in an unknown pool
+            match contract._run_raffle(3, 1, 1000, None) {
+                Err(RaffleError::PoolNotFound) => debug_println!("PoolNotFound as expected"),
+                _ => panic!("PoolNotFound is expected"),
+            };
+        }
+
+        const ORACLE_SECRET_KEY: [u8; 32] = [0xcd; 32];
+
+        /// return the public key of the oracle used in the tests
+        fn get_oracle_public_key() -> [u8; 33] {
+            let secp = Secp256k1::new();
+            let secret_key = SecretKey::from_slice(&ORACLE_SECRET_KEY).unwrap();
+            PublicKey::from_secret_key(&secp, &secret_key).serialize()
+        }
+
+        /// sign the seed like the oracle does
+        fn sign_seed(secret_key: &[u8; 32], era: u32, request_id: u32, seed: [u8; 32]) -> Vec<u8> {
+            let secp = Secp256k1::new();
+            let secret_key = SecretKey::from_slice(secret_key).unwrap();
+            let message = Message::from_slice(&get_randomness_message(era, request_id, seed)).unwrap();
+            let (recovery_id, signature) = secp.sign_ecdsa_recoverable(&message, &secret_key).serialize_compact();
+            let mut proof = signature.to_vec();
+            proof.push(recovery_id.to_i32() as u8);
+            proof
+        }
+
+        /// return the other valid signature (r, n - s) with the opposite recovery id
+        fn to_high_s(mut proof: Vec<u8>) -> Vec<u8> {
+            // order of the secp256k1 curve
+            const ORDER: [u8; 32] = [
+                0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+                0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+            ];
+            let mut borrow = 0i16;
+            for i in (0..32).rev() {
+                let mut value = ORDER[i] as i16 - proof[32 + i] as i16 - borrow;
+                borrow = if value < 0 { value += 256; 1 } else { 0 };
+                proof[32 + i] = value as u8;
+            }
+            proof[64] ^= 1;
+            proof
+        }
+
+        #[ink::test]
+        fn test_run_raffle_distinct_winners_with_duplicates() {
+            let mut contract = super::Contract::new();
+            let accounts = accounts();
+
+            // alice has many entries with a huge weight
+            let mut participants = vec![(accounts.alice, 1_000_000); 10];
+            participants.push((accounts.bob, 1));
+            participants.push((accounts.alice, 1_000_000));
+            participants.push((accounts.charlie, 1));
+            participants.push((accounts.alice, 1_000_000));
+
+            contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 100).unwrap();
+            contract.set_distinct_winners(POOL_ID, true).unwrap();
+            assert!(contract.is_distinct_winners(POOL_ID));
+
+            for era in 1..=5 {
+                contract.add_participants_with_filters(POOL_ID, era, participants.clone()).unwrap();
+                let winners = contract._run_raffle(POOL_ID, era, 1000, None).unwrap();
+
+                // alice cannot win more than one prize, bob and charlie are the other winners
+                assert_eq!(winners.len(), 3);
+                assert_eq!(winners[0].0, accounts.alice);
+                let mut accounts_won: Vec<AccountId> = winners.iter().map(|(account, _)| *account).collect();
+                accounts_won.sort();
+                let mut expected = vec![accounts.alice, accounts.bob, accounts.charlie];
+                expected.sort();
+                assert_eq!(accounts_won, expected);
+            }
+        }
+
+        #[ink::test]
+        fn test_run_raffle_distinct_winners_not_enough_participants() {
+            let mut contract = super::Contract::new();
+            let accounts = accounts();
+
+            // 10 entries but only 2 accounts
+            let mut participants = vec![(accounts.alice, 100); 5];
+            participants.append(&mut vec![(accounts.bob, 100); 5]);
+            contract.add_participants_with_filters(POOL_ID, 1, participants).unwrap();
+
+            contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 100).unwrap();
+            contract.set_distinct_winners(POOL_ID, true).unwrap();
+
+            match contract._run_raffle(POOL_ID, 1, 1000, None) {
+                Err(RaffleError::NotEnoughParticipants) => debug_println!("NotEnoughParticipants as expected"),
+                _ => panic!("Error 1"),
+            };
+
+            // less entries than prizes
+            contract.clear_data(POOL_ID).unwrap();
+            contract.add_participants_with_filters(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.bob, 100)]).unwrap();
+
+            match contract._run_raffle(POOL_ID, 1, 1000, None) {
+                Err(RaffleError::NotEnoughParticipants) => debug_println!("NotEnoughParticipants as expected"),
+                _ => panic!("Error 2"),
+            };
+
+            // the same account can win many prizes when the option is disabled
+            contract.set_distinct_winners(POOL_ID, false).unwrap();
+            let winners = contract._run_raffle(POOL_ID, 1, 1000, None).unwrap();
+            assert_eq!(winners.len(), 3);
+        }
+
+        #[ink::test]
+        fn test_run_raffle_distinct_winners_share_full_rewards() {
+            let mut contract = super::Contract::new();
+            let accounts = accounts();
+
+            let participants = vec![
+                (accounts.alice, 100000), (accounts.bob, 100000), (accounts.charlie, 100000),
+                (accounts.django, 100000), (accounts.eve, 100000), (accounts.frank, 100000)
+                ];
+            contract.add_participants_with_filters(POOL_ID, 1, participants).unwrap();
+
+            contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 100).unwrap();
+            contract.set_distinct_winners(POOL_ID, true).unwrap();
+
+            let winners = contract._run_raffle(POOL_ID, 1, 1000, None).unwrap();
+
+            // assert three different accounts
+            assert_eq!(winners.len(), 3);
+            assert_ne!(winners[0].0, winners[1].0);
+            assert_ne!(winners[0].0, winners[2].0);
+            assert_ne!(winners[1].0, winners[2].0);
+
+            let mut total_rewards = 0;
+            for (_, r) in  winners {
+                total_rewards += r;
+            }
+            assert_eq!(total_rewards, 1000);
+        }
+
+        #[ink::test]
+        fn test_distinct_winners_by_pool() {
+            let mut contract = super::Contract::new();
+            let accounts = accounts();
+
+            let other_pool = 2;
+            contract._create_pool(other_pool).unwrap();
+
+            contract.set_distinct_winners(POOL_ID, true).unwrap();
+            assert!(contract.is_distinct_winners(POOL_ID));
+            // the option is not set for the other pool
+            assert!(!contract.is_distinct_winners(other_pool));
+
+            // only one account in both pools
+            let participants = vec![(accounts.alice, 100); 3];
+            contract.add_participants_with_filters(POOL_ID, 1, participants.clone()).unwrap();
+            contract.add_participants_with_filters(other_pool, 1, participants).unwrap();
+            contract.set_ratio_distribution(POOL_ID, vec![50, 50], 100).unwrap();
+            contract.set_ratio_distribution(other_pool, vec![50, 50], 100).unwrap();
+
+            match contract._run_raffle(POOL_ID, 1, 1000, None) {
+                Err(RaffleError::NotEnoughParticipants) => debug_println!("NotEnoughParticipants as expected"),
+                _ => panic!("Error 1"),
+            };
+
+            // the same account wins both prizes in the other pool
+            let winners = contract._run_raffle(other_pool, 1, 1000, None).unwrap();
+            assert_eq!(winners, vec![(accounts.alice, 500), (accounts.alice, 500)]);
+        }
+
+        #[ink::test]
+        fn test_start_and_finish_raffle_distinct_winners() {
+            let mut contract = super::Contract::new();
+            let accounts = accounts();
+
+            let mut participants = vec![(accounts.alice, 1_000_000); 10];
+            participants.push((accounts.bob, 1));
+            participants.push((accounts.charlie, 1));
+            contract.add_participants_with_filters(POOL_ID, 1, participants).unwrap();
+
+            contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 100).unwrap();
+            contract.set_distinct_winners(POOL_ID, true).unwrap();
+
+            let request_id = contract._start_raffle(POOL_ID, 1, 1000, None).unwrap();
+            mock_fulfill(&mut contract, request_id);
+            let (_, winners) = contract._finish_raffle(POOL_ID).unwrap();
+
+            assert_eq!(winners.len(), 3);
+            assert_ne!(winners[0].0, winners[1].0);
+            assert_ne!(winners[0].0, winners[2].0);
+            assert_ne!(winners[1].0, winners[2].0);
+        }
+
+        /// mock of the off-chain worker providing the random seed for the request
+        fn mock_fulfill(contract: &mut super::Contract, request_id: u32) {
+            if contract.get_oracle_public_key().is_none() {
+                contract.set_oracle_public_key(get_oracle_public_key()).unwrap();
+            }
+            let era = contract.get_request_era(request_id).unwrap();
+            let seed = contract.get_request_seed(request_id).unwrap();
+            let proof = sign_seed(&ORACLE_SECRET_KEY, era, request_id, seed);
+            contract.fulfill_randomness(request_id, proof).unwrap();
+        }
+
+        #[ink::test]
+        fn test_start_and_finish_raffle() {
+            let mut contract = super::Contract::new();
+            let accounts = accounts();
+
+            let participants = vec![
+                (accounts.alice, 100000), (accounts.bob, 100000), (accounts.charlie, 100000), 
+                (accounts.django, 100000), (accounts.eve, 100000), (accounts.frank, 100000)
+                ];
+            contract.add_participants_with_filters(POOL_ID, 1, participants).unwrap();
+
+            contract.set_ratio_distribution(POOL_ID, vec![50, 30, 20], 100).unwrap();
+
+            match contract._finish_raffle(POOL_ID) {
+                Err(RaffleError::NoPendingRaffle) => debug_println!("NoPendingRaffle as expected"),
+                _ => panic!("Error 1"),
+            };
+
+            // start the raffle
+            let request_id = contract._start_raffle(POOL_ID, 1, 1000, None).unwrap();
+            assert_eq!(
+                contract.get_pending_raffle(POOL_ID),
+                Some(PendingRaffle {
+                    era: 1,
+                    total_rewards: 1000,
+                    request_id,
+                    nb_participants: 6,
+                    total_value: 600000,
+                    settings: RaffleSettings { ratio_distribution: vec![50, 30, 20], total_ratio_distribution: 100, distinct_winners: false },
+                    started_at: ink::env::block_timestamp::<ink::env::DefaultEnvironment>(),
+                })
+            );
+            assert_eq!(contract.get_request_era(request_id), Some(1));
+
+            // the raffle cannot be started twice
+            match contract._start_raffle(POOL_ID, 2, 1000, None) {
+                Err(RaffleError::RaffleAlreadyPending) => debug_println!("RaffleAlreadyPending as expected"),
+                _ => panic!("Error 2"),
+            };
+
+            // the seed is not provided yet
+            match contract._finish_raffle(POOL_ID) {
+                Err(RaffleError::RandomError(RandomError::RandomnessNotFulfilled)) => debug_println!("RandomnessNotFulfilled as expected"),
+                _ => panic!("Error 3"),
+            };
+
+            // the oracle provides the seed
+            mock_fulfill(&mut contract, request_id);
+
+            // finish the raffle
+            let (pending_raffle, winners) = contract._finish_raffle(POOL_ID).unwrap();
+            assert_eq!(pending_raffle.era, 1);
+            assert_eq!(contract.get_pending_raffle(POOL_ID), None);
+            assert_eq!(contract.get_last_era_done(POOL_ID), 1);
+
+            assert_eq!(winners.len(), 3);
+            let mut total_rewards = 0;
+            for (_, r) in  winners {
+                total_rewards += r;
+            }
+            // assert all rewards are given
+            assert_eq!(total_rewards, 1000);
+
+            // the raffle is done for this era
+            match contract._start_raffle(POOL_ID, 1, 1000, None) {
+                Err(RaffleError::RaffleAlreadyDone) => debug_println!("RaffleAlreadyDone as expected"),
+                _ => panic!("Error 4"),
+            };
+        }
+
+        #[ink::test]
+        fn test_settings_saved_with_pending_raffle() {
+            let mut contract = super::Contract::new();
+            let accounts = accounts();
+
+            contract.add_participants_with_filters(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.bob, 200)]).unwrap();
+            contract.set_ratio_distribution(POOL_ID, vec![100], 100).unwrap();
+
+            let request_id = contract._start_raffle(POOL_ID, 1, 1000, None).unwrap();
+            mock_fulfill(&mut contract, request_id);
+
+            // the settings changed once the seed is known are not used
+            contract.set_ratio_distribution(POOL_ID, vec![50, 50], 100).unwrap();
+            contract.set_distinct_winners(POOL_ID, true).unwrap();
+
+            let (_, winners) = contract._finish_raffle(POOL_ID).unwrap();
+            assert_eq!(winners.len(), 1);
+            assert_eq!(winners[0].1, 1000);
+        }
+
+        #[ink::test]
+        fn test_finish_raffle_pool_closed() {
+            let mut contract = super::Contract::new();
+            let accounts = accounts();
+
+            contract.add_participants_with_filters(POOL_ID, 1, vec![(accounts.alice, 100)]).unwrap();
+            contract.set_ratio_distribution(POOL_ID, vec![100], 100).unwrap();
+
+            let request_id = contract._start_raffle(POOL_ID, 1, 1000, None).unwrap();
+            mock_fulfill(&mut contract, request_id);
+
+            contract._close_pool(POOL_ID).unwrap();
+            match contract._finish_raffle(POOL_ID) {
+                Err(RaffleError::PoolNotFound) => debug_println!("PoolNotFound as expected"),
+                _ => panic!("Error 1"),
+            };
+        }
+
+        #[ink::test]
+        fn test_commit_secret_before_participants() {
+            let mut contract = super::Contract::new();
+            let accounts = accounts();
+
+            // the secret is committed before the participants are added
+            contract._commit_secret(1, Hash::from([1u8; 32])).unwrap();
+            assert_eq!(contract.get_commitment(1), Some(Hash::from([1u8; 32])));
+            contract.add_participants_with_filters(POOL_ID, 1, vec![(accounts.alice, 100)]).unwrap();
+
+            // the secret can't be committed once the participants are known
+            contract.add_participants_with_filters(POOL_ID, 2, vec![(accounts.alice, 100)]).unwrap();
+            match contract._commit_secret(2, Hash::from([2u8; 32])) {
+                Err(RaffleError::ParticipantsAlreadyAdded) => debug_println!("ParticipantsAlreadyAdded as expected"),
+                _ => panic!("Error 1"),
+            }
+            assert_eq!(contract.get_commitment(2), None);
+
+            // only the raffle manager can commit the secret
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            match contract._commit_secret(3, Hash::from([3u8; 32])) {
+                Err(RaffleError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Error 2"),
+            }
+        }
+
+        #[ink::test]
+        fn test_cancel_raffle() {
+            let mut contract = super::Contract::new();
+            let accounts = accounts();
+
+            contract.add_participants_with_filters(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.bob, 200)]).unwrap();
+            contract.set_ratio_distribution(POOL_ID, vec![100], 100).unwrap();
+
+            match contract._cancel_raffle(POOL_ID) {
+                Err(RaffleError::NoPendingRaffle) => debug_println!("NoPendingRaffle as expected"),
+                _ => panic!("Error 1"),
+            };
+
+            let request_id = contract._start_raffle(POOL_ID, 1, 1000, None).unwrap();
+
+            // the oracle has some time to provide the seed
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(PENDING_RAFFLE_TIMEOUT - 1);
+            match contract._cancel_raffle(POOL_ID) {
+                Err(RaffleError::PendingRaffleNotExpired) => debug_println!("PendingRaffleNotExpired as expected"),
+                _ => panic!("Error 2"),
+            };
+
+            // only the raffle manager can cancel the raffle
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(PENDING_RAFFLE_TIMEOUT);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            match contract._cancel_raffle(POOL_ID) {
+                Err(RaffleError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Error 3"),
+            };
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+
+            let pending_raffle = contract._cancel_raffle(POOL_ID).unwrap();
+            assert_eq!(pending_raffle.request_id, request_id);
+            assert_eq!(contract.get_pending_raffle(POOL_ID), None);
+
+            // the raffle can be started again for the same era
+            let request_id = contract._start_raffle(POOL_ID, 1, 1000, None).unwrap();
+            mock_fulfill(&mut contract, request_id);
+
+            // the raffle can't be cancelled once the seed is provided
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(3 * PENDING_RAFFLE_TIMEOUT);
+            match contract._cancel_raffle(POOL_ID) {
+                Err(RaffleError::SeedAlreadyProvided) => debug_println!("SeedAlreadyProvided as expected"),
+                _ => panic!("Error 4"),
+            };
+            let (_, winners) = contract._finish_raffle(POOL_ID).unwrap();
+            assert_eq!(winners.len(), 1);
+        }
+
+        #[ink::test]
+        fn test_participants_locked_during_pending_raffle() {
+            let mut contract = super::Contract::new();
+            let accounts = accounts();
+
+            contract.add_participants_with_filters(POOL_ID, 1, vec![(accounts.alice, 100), (accounts.bob, 200)]).unwrap();
+            contract.set_ratio_distribution(POOL_ID, vec![100], 100).unwrap();
+            assert_eq!(contract.get_locked_era(POOL_ID), None);
+
+            let request_id = contract._start_raffle(POOL_ID, 1, 1000, None).unwrap();
+            assert_eq!(contract.get_locked_era(POOL_ID), Some(1));
+
+            // the participants can't be changed while the raffle is waiting for the seed
+            match contract.add_participants(POOL_ID, 1, vec![(accounts.charlie, 1000)]) {
+                Err(ParticipantManagerError::EraLocked) => debug_println!("EraLocked as expected"),
+                _ => panic!("Error 1"),
+            };
+            match contract.update_participant(POOL_ID, accounts.alice, 1000) {
+                Err(ParticipantManagerError::EraLocked) => debug_println!("EraLocked as expected"),
+                _ => panic!("Error 2"),
+            };
+            match contract.remove_participant(POOL_ID, accounts.bob) {
+                Err(ParticipantManagerError::EraLocked) => debug_println!("EraLocked as expected"),
+                _ => panic!("Error 3"),
+            };
+            match contract.clear_data(POOL_ID) {
+                Err(ParticipantManagerError::EraLocked) => debug_println!("EraLocked as expected"),
+                _ => panic!("Error 4"),
+            };
+            assert_eq!(contract.get_nb_participants_for_era(POOL_ID, 1), 2);
+            assert_eq!(contract.get_total_value_for_era(POOL_ID, 1), 300);
+
+            // the next era is not locked
+            contract.add_participants(POOL_ID, 2, vec![(accounts.charlie, 1000)]).unwrap();
+
+            mock_fulfill(&mut contract, request_id);
+            let (pending_raffle, winners) = contract._finish_raffle(POOL_ID).unwrap();
+            assert_eq!(pending_raffle.nb_participants, 2);
+            assert_eq!(pending_raffle.total_value, 300);
+            assert_eq!(winners.len(), 1);
+        }
+
+        #[ink::test]
+        fn test_fulfill_randomness() {
+            let mut contract = super::Contract::new();
+
+            match contract.fulfill_randomness(0, Vec::new()) {
+                Err(RandomGeneratorError::RequestNotFound) => debug_println!("RequestNotFound as expected"),
+                _ => panic!("Error 1"),
+            };
+
+            let request_id = contract.request_randomness(1, None).unwrap();
+            assert_eq!(contract.get_randomness(request_id), None);
+            let seed = contract.get_request_seed(request_id).unwrap();
+
+            // no key registered for the oracle
+            let proof = sign_seed(&ORACLE_SECRET_KEY, 1, request_id, seed);
+            match contract.fulfill_randomness(request_id, proof) {
+                Err(RandomGeneratorError::MissingOraclePublicKey) => debug_println!("MissingOraclePublicKey as expected"),
+                _ => panic!("Error 2"),
+            };
+
+            contract.set_oracle_public_key(get_oracle_public_key()).unwrap();
+            assert_eq!(contract.get_oracle_public_key(), Some(get_oracle_public_key()));
+
+            // the oracle can't choose the seed
+            let proof = sign_seed(&ORACLE_SECRET_KEY, 1, request_id, [2u8; 32]);
+            match contract.fulfill_randomness(request_id, proof) {
+                Err(RandomGeneratorError::InvalidProof) => debug_println!("InvalidProof as expected"),
+                _ => panic!("Error 3"),
+            };
+
+            // the proof has not the right length
+            match contract.fulfill_randomness(request_id, vec![1u8; 10]) {
+                Err(RandomGeneratorError::InvalidProof) => debug_println!("InvalidProof as expected"),
+                _ => panic!("Error 4"),
+            };
+
+            // the seed is signed by another key
+            let proof = sign_seed(&[0xab; 32], 1, request_id, seed);
+            match contract.fulfill_randomness(request_id, proof) {
+                Err(RandomGeneratorError::InvalidProof) => debug_println!("InvalidProof as expected"),
+                _ => panic!("Error 5"),
+            };
+
+            // the malleated signature (high s value) is rejected
+            let proof = to_high_s(sign_seed(&ORACLE_SECRET_KEY, 1, request_id, seed));
+            match contract.fulfill_randomness(request_id, proof) {
+                Err(RandomGeneratorError::InvalidProof) => debug_println!("InvalidProof as expected"),
+                _ => panic!("Error 6"),
+            };
+
+            let proof = sign_seed(&ORACLE_SECRET_KEY, 1, request_id, seed);
+            contract.fulfill_randomness(request_id, proof.clone()).unwrap();
+            // the randomness is derived from the proof
+            let mut randomness = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Keccak256>(&proof, &mut randomness);
+            assert_eq!(contract.get_randomness(request_id), Some(randomness));
+
+            match contract.fulfill_randomness(request_id, proof) {
+                Err(RandomGeneratorError::RequestAlreadyFulfilled) => debug_println!("RequestAlreadyFulfilled as expected"),
+                _ => panic!("Error 7"),
+            };
+
+            // the seed of the next request depends on the previous randomness
+            let request_id = contract.request_randomness(2, None).unwrap();
+            assert_ne!(contract.get_request_seed(request_id), Some(seed));
+
+            // only the oracle can provide the seed
+            let proof = sign_seed(&ORACLE_SECRET_KEY, 2, request_id, contract.get_request_seed(request_id).unwrap());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts().bob);
+            match contract.fulfill_randomness(request_id, proof) {
+                Err(RandomGeneratorError::AccessControlError(AccessControlError::MissingRole)) => debug_println!("MissingRole as expected"),
+                _ => panic!("Error 8"),
+            };
+        }
+
+        #[ink::test]
+        fn test_rotate_oracle_public_key() {
+            let mut contract = super::Contract::new();
+
+            let new_secret_key = [0xab; 32];
+            let secp = Secp256k1::new();
+            let new_public_key = PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&new_secret_key).unwrap()).serialize();
+
+            contract.set_oracle_public_key(get_oracle_public_key()).unwrap();
+            let request_id = contract.request_randomness(1, None).unwrap();
+            let seed = contract.get_request_seed(request_id).unwrap();
+
+            // rotate the key
+            contract.set_oracle_public_key(new_public_key).unwrap();
+            assert_eq!(contract.get_oracle_public_key(), Some(new_public_key));
+
+            // the old key is not accepted anymore
+            let proof = sign_seed(&ORACLE_SECRET_KEY, 1, request_id, seed);
+            match contract.fulfill_randomness(request_id, proof) {
+                Err(RandomGeneratorError::InvalidProof) => debug_println!("InvalidProof as expected"),
+                _ => panic!("Error 1"),
+            };
+
+            let proof = sign_seed(&new_secret_key, 1, request_id, seed);
+            contract.fulfill_randomness(request_id, proof).unwrap();
+            assert!(contract.get_randomness(request_id).is_some());
         }
 
         pub fn get_reward(contract: &mut super::Contract, account: AccountId) -> Option<u128> {
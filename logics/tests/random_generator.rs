@@ -9,6 +9,7 @@ pub mod random_generator {
 
     use lucky::impls::{
         *,
+        random_generator,
         random_generator::*,
     };
 
@@ -24,6 +25,15 @@ pub mod random_generator {
     impl RandomGenerator for Contract {}
     impl AccessControl for Contract{}
 
+    impl random_generator::Internal for Contract {
+        fn _emit_oracle_public_key_set_event(&self, _public_key: [u8; 33]){
+            // no event for the tests
+        }
+        fn _emit_randomness_verified_event(&self, _request_id: u32, _era: u32, _randomness: [u8; 32]){
+            // no event for the tests
+        }
+    }
+
     impl Contract {
         #[ink(constructor)]
         pub fn new() -> Self {
@@ -39,6 +49,9 @@ pub mod random_generator {
     }
 
     mod tests {
+        use std::collections::HashSet;
+        use ink::env::debug_println;
+        use ink::env::hash::{Keccak256, HashOutput};
 
         use super::*;
 
@@ -53,5 +66,184 @@ pub mod random_generator {
                 }
             }
         }
+
+        #[ink::test]
+        fn test_random_number_uniform_distribution() {
+            let mut contract = Contract::new();
+
+            let nb_values = 10;
+            let nb_draws = 10_000;
+            let mut counts = vec![0u32; nb_values];
+            for _ in 0..nb_draws {
+                let result = contract.get_random_number(0, nb_values as u128 - 1).unwrap();
+                counts[result as usize] += 1;
+            }
+
+            // chi-squared test with 9 degrees of freedom: 27.88 is the critical value for p = 0.001
+            let expected = nb_draws as f64 / nb_values as f64;
+            let chi_squared: f64 = counts.iter()
+                .map(|count| (*count as f64 - expected).powi(2) / expected)
+                .sum();
+            debug_println!("counts: {:?} - chi squared: {}", counts, chi_squared);
+            assert!(chi_squared < 27.88);
+        }
+
+        #[ink::test]
+        fn test_random_number_max_not_over_represented() {
+            let mut contract = Contract::new();
+
+            let nb_draws = 10_000;
+            let mut nb_max = 0;
+            for _ in 0..nb_draws {
+                if contract.get_random_number(0, 1).unwrap() == 1 {
+                    nb_max += 1;
+                }
+            }
+            // expected 5000, the standard deviation is 50
+            assert!(nb_max > 4800);
+            assert!(nb_max < 5200);
+        }
+
+        #[ink::test]
+        fn test_random_number_large_range() {
+            let mut contract = Contract::new();
+
+            let max_value = 10_000_000;
+            let nb_draws = 1_000;
+            let mut values = HashSet::new();
+            let mut sum = 0;
+            for _ in 0..nb_draws {
+                let result = contract.get_random_number(1, max_value).unwrap();
+                assert!(result >= 1);
+                assert!(result <= max_value);
+                values.insert(result);
+                sum += result;
+            }
+            // far more than the 256 distinct values reachable with a single byte
+            assert!(values.len() > 990);
+
+            // the mean should be close to the middle of the range
+            let mean = sum / nb_draws;
+            assert!(mean > max_value * 45 / 100);
+            assert!(mean < max_value * 55 / 100);
+        }
+
+        #[ink::test]
+        fn test_get_random_numbers() {
+            let mut contract = Contract::new();
+
+            let salt = contract.get_salt().unwrap();
+            let randoms = contract.get_random_numbers(100, 1, 1_000_000).unwrap();
+            // only one seed is used for all numbers
+            assert_eq!(contract.get_salt().unwrap(), salt + 1);
+
+            assert_eq!(randoms.len(), 100);
+            for random in &randoms {
+                assert!(*random >= 1);
+                assert!(*random <= 1_000_000);
+            }
+            let distinct_values: HashSet<u128> = randoms.into_iter().collect();
+            assert!(distinct_values.len() > 95);
+
+            assert_eq!(contract.get_random_numbers(0, 1, 10).unwrap().len(), 0);
+        }
+
+        #[ink::test]
+        fn test_get_random_numbers_uniform_distribution() {
+            let mut contract = Contract::new();
+
+            let nb_values = 10;
+            let mut counts = vec![0u32; nb_values];
+            for _ in 0..100 {
+                for random in contract.get_random_numbers(100, 0, nb_values as u128 - 1).unwrap() {
+                    counts[random as usize] += 1;
+                }
+            }
+
+            // chi-squared test with 9 degrees of freedom: 27.88 is the critical value for p = 0.001
+            let expected = 10_000f64 / nb_values as f64;
+            let chi_squared: f64 = counts.iter()
+                .map(|count| (*count as f64 - expected).powi(2) / expected)
+                .sum();
+            debug_println!("counts: {:?} - chi squared: {}", counts, chi_squared);
+            assert!(chi_squared < 27.88);
+        }
+
+        fn get_commitment(secret: &[u8; 32]) -> Hash {
+            let mut output = <Keccak256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Keccak256>(secret, &mut output);
+            Hash::from(output)
+        }
+
+        #[ink::test]
+        fn test_commit_reveal() {
+            let mut contract = Contract::new();
+
+            let secret = [7u8; 32];
+            let commitment = get_commitment(&secret);
+
+            assert_eq!(contract.get_commitment(1), None);
+            assert!(!contract.is_secret_revealed(1));
+
+            contract.commit_secret(1, commitment).unwrap();
+            assert_eq!(contract.get_commitment(1), Some(commitment));
+            assert!(!contract.is_secret_revealed(1));
+
+            // the secret is revealed with the draw
+            let randoms = contract.get_random_numbers_with_secret(1, secret, 10, 1, 100).unwrap();
+            assert_eq!(randoms.len(), 10);
+            assert!(contract.is_secret_revealed(1));
+            // the other eras are not impacted
+            assert!(!contract.is_secret_revealed(2));
+
+            match contract.get_random_numbers_with_secret(1, secret, 10, 1, 100) {
+                Err(RandomGeneratorError::SecretAlreadyRevealed) => debug_println!("SecretAlreadyRevealed as expected"),
+                _ => panic!("The secret should not be revealed twice"),
+            }
+        }
+
+        #[ink::test]
+        fn test_commit_twice() {
+            let mut contract = Contract::new();
+
+            contract.commit_secret(1, get_commitment(&[1u8; 32])).unwrap();
+
+            match contract.commit_secret(1, get_commitment(&[2u8; 32])) {
+                Err(RandomGeneratorError::CommitmentAlreadyExists) => debug_println!("CommitmentAlreadyExists as expected"),
+                _ => panic!("The commitment should not be overridden"),
+            }
+            assert_eq!(contract.get_commitment(1), Some(get_commitment(&[1u8; 32])));
+        }
+
+        #[ink::test]
+        fn test_reveal_invalid_secret() {
+            let mut contract = Contract::new();
+
+            match contract.get_random_numbers_with_secret(1, [1u8; 32], 1, 1, 100) {
+                Err(RandomGeneratorError::MissingCommitment) => debug_println!("MissingCommitment as expected"),
+                _ => panic!("The secret should not be revealed without commitment"),
+            }
+
+            contract.commit_secret(1, get_commitment(&[1u8; 32])).unwrap();
+
+            match contract.get_random_numbers_with_secret(1, [2u8; 32], 1, 1, 100) {
+                Err(RandomGeneratorError::InvalidSecret) => debug_println!("InvalidSecret as expected"),
+                _ => panic!("The secret should match the commitment"),
+            }
+            assert!(!contract.is_secret_revealed(1));
+        }
+
+        #[ink::test]
+        fn test_uniform_random_number() {
+            let output = [0xffu8; 32];
+            assert_eq!(get_uniform_random_number(output, 5, 5).unwrap(), 5);
+            assert_eq!(get_uniform_random_number(output, 0, u128::MAX).unwrap(), u128::MAX);
+            assert_eq!(get_uniform_random_number(output, 1, 10).unwrap(), 1 + u128::MAX % 10);
+
+            match get_uniform_random_number(output, 10, 5) {
+                Err(RandomGeneratorError::SubOverFlow) => debug_println!("SubOverFlow as expected"),
+                _ => panic!("max lower than min should fail"),
+            }
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,74 @@
+use ink::env::{DefaultEnvironment, Environment};
+use ink::prelude::vec::Vec;
+use ink::primitives::AccountId;
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+
+/// Functions of the dApps Staking chain extension provided by Astar
+#[ink::chain_extension]
+pub trait DappsStakingExtension {
+    type ErrorCode = DappsStakingError;
+
+    /// return the current era of dApps Staking
+    #[ink(extension = 1, handle_status = false)]
+    fn read_current_era() -> u32;
+
+    /// return the amount staked by the staker on the given contract
+    #[ink(extension = 6, handle_status = false)]
+    fn read_staked_amount_on_contract(staker: AccountId, contract: AccountId) -> Balance;
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum DappsStakingError {
+    UnknownStatusCode,
+}
+
+impl ink::env::chain_extension::FromStatusCode for DappsStakingError {
+    fn from_status_code(status_code: u32) -> Result<(), Self> {
+        match status_code {
+            0 => Ok(()),
+            _ => Err(DappsStakingError::UnknownStatusCode),
+        }
+    }
+}
+
+/// Environment of the contracts using the dApps Staking chain extension
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum CustomEnvironment {}
+
+impl Environment for CustomEnvironment {
+    const MAX_EVENT_TOPICS: usize = <DefaultEnvironment as Environment>::MAX_EVENT_TOPICS;
+
+    type AccountId = <DefaultEnvironment as Environment>::AccountId;
+    type Balance = <DefaultEnvironment as Environment>::Balance;
+    type Hash = <DefaultEnvironment as Environment>::Hash;
+    type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+    type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
+
+    type ChainExtension = DappsStakingExtension;
+}
+
+#[openbrush::trait_definition]
+pub trait DappsStakingReader {
+
+    /// return the given stakers with the amount they staked on the dApp, the stakers without stake are ignored.
+    /// The era must be the current era of dApps Staking: the pages built for a previous era are rejected
+    fn _read_stakes(&self, dapp_address: AccountId, era: u32, stakers: Vec<AccountId>) -> Result<Vec<(AccountId, Balance)>, DappsStakingReaderError>;
+
+}
+
+#[openbrush::trait_definition]
+pub trait Internal {
+    /// return the current era of dApps Staking, read with the chain extension
+    fn _read_current_era(&self) -> u32;
+    /// return the amount staked by the staker on the given contract, read with the chain extension
+    fn _read_staked_amount_on_contract(&self, staker: AccountId, contract: AccountId) -> Balance;
+}
+
+#[derive(Debug, Eq, PartialEq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum DappsStakingReaderError {
+    InvalidEra,
+}
@@ -0,0 +1,107 @@
+use ink::prelude::vec::Vec;
+use openbrush::contracts::access_control::AccessControlError;
+use openbrush::traits::{AccountId, Balance};
+
+/// Leaf of the Merkle tree: the participant covers the weights in the range ]start, end]
+/// where start is the cumulative weight of the previous participants and end = start + weight
+#[derive(Debug, Eq, PartialEq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct ParticipantLeaf {
+    pub account: AccountId,
+    pub start: Balance,
+    pub end: Balance,
+}
+
+/// Sibling of a node in the Merkle proof
+#[derive(Debug, Eq, PartialEq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct ProofNode {
+    pub hash: [u8; 32],
+    /// total weight of the leaves under this node
+    pub value: Balance,
+    /// true if the sibling is on the left of the node
+    pub is_left: bool,
+}
+
+/// Root of the Merkle tree built with the participants of an era
+#[derive(Debug, Eq, PartialEq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct ParticipantsRoot {
+    pub root: [u8; 32],
+    /// number of leaves of the tree
+    pub nb_participants: u32,
+    /// total weight of the participants (end of the last leaf)
+    pub total_value: Balance,
+}
+
+/// Weight offset selected by the raffle, the participant covering this offset wins the reward
+#[derive(Debug, Eq, PartialEq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct WinningOffset {
+    pub offset: Balance,
+    pub reward: Balance,
+    /// winner proven for this offset
+    pub winner: Option<AccountId>,
+}
+
+#[openbrush::trait_definition]
+pub trait MerkleParticipants {
+
+    /// Set the Merkle root of the participants of the pool for the given era.
+    /// The total weight is read from the last leaf of the tree, proven with the given proof.
+    /// The number of participants (leaves of the tree) must be consistent with the proof of the last leaf.
+    /// Only the root is stored, the participants are proven when they win.
+    /// The participants are not checked by the filters: the tree must be built with the eligible participants
+    #[ink(message)]
+    fn set_participants_root(
+        &mut self,
+        pool_id: u32,
+        era: u32,
+        root: [u8; 32],
+        nb_participants: u32,
+        last_leaf: ParticipantLeaf,
+        proof: Vec<ProofNode>
+    ) -> Result<(), MerkleParticipantsError>;
+
+    #[ink(message)]
+    fn get_participants_root(&self, pool_id: u32, era: u32) -> Option<ParticipantsRoot>;
+
+    /// return the weight offsets selected by the raffle of the pool for the given era
+    #[ink(message)]
+    fn get_winning_offsets(&self, pool_id: u32, era: u32) -> Vec<WinningOffset>;
+
+    /// save the weight offsets selected by the raffle and the rewards for the given pool and era
+    fn _publish_winning_offsets(&mut self, pool_id: u32, era: u32, offsets: Vec<(Balance, Balance)>) -> Result<(), MerkleParticipantsError>;
+
+    /// check the leaf covers the winning offset at the given index and belongs to the tree of the pool and era.
+    /// return the reward for this winner. The offset cannot be proven twice
+    fn _prove_winner(
+        &mut self,
+        pool_id: u32,
+        era: u32,
+        index: u32,
+        leaf: ParticipantLeaf,
+        proof: Vec<ProofNode>
+    ) -> Result<Balance, MerkleParticipantsError>;
+
+}
+
+#[derive(Debug, Eq, PartialEq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum MerkleParticipantsError {
+    RootAlreadySet,
+    RootNotFound,
+    OffsetsAlreadyPublished,
+    OffsetNotFound,
+    OffsetAlreadyProven,
+    OffsetNotCovered,
+    InvalidProof,
+    AccessControlError(AccessControlError),
+}
+
+/// convertor from AccessControlError to MerkleParticipantsError
+impl From<AccessControlError> for MerkleParticipantsError {
+    fn from(error: AccessControlError) -> Self {
+        MerkleParticipantsError::AccessControlError(error)
+    }
+}
@@ -1,5 +1,7 @@
 pub mod participant_manager;
 pub mod participant_filter;
+pub mod merkle_participants;
+pub mod dapps_staking;
 pub mod random;
 pub mod random_generator;
 pub mod reward;
@@ -0,0 +1,26 @@
+use ink::prelude::vec::Vec;
+use openbrush::traits::AccountId;
+use crate::traits::participant_filter::participant_filter::ParticipantFilterError;
+
+#[openbrush::trait_definition]
+pub trait FilterContractAccounts {
+
+    /// Add the smart contracts in the allowlist: these contracts can participate in the raffles.
+    /// The contracts already allowed are ignored
+    #[ink(message)]
+    fn add_to_contract_allowlist(&mut self, accounts: Vec<AccountId>) -> Result<(), ParticipantFilterError>;
+
+    /// Remove the smart contracts from the allowlist. The contracts not allowed are ignored
+    #[ink(message)]
+    fn remove_from_contract_allowlist(&mut self, accounts: Vec<AccountId>) -> Result<(), ParticipantFilterError>;
+
+    #[ink(message)]
+    fn is_contract_allowed(&self, account: AccountId) -> bool;
+
+}
+
+#[openbrush::trait_definition]
+pub trait Internal {
+    /// return true if the account is a smart contract in the environment of the contract
+    fn _is_contract(&self, account: &AccountId) -> bool;
+}
@@ -0,0 +1,33 @@
+use ink::prelude::vec::Vec;
+use openbrush::traits::AccountId;
+use crate::traits::participant_filter::participant_filter::ParticipantFilterError;
+
+#[openbrush::trait_definition]
+pub trait FilterDenylist {
+
+    /// Add the accounts in the denylist: these accounts are excluded from the raffles of all pools.
+    /// The accounts already denied are ignored
+    #[ink(message)]
+    fn add_to_denylist(&mut self, accounts: Vec<AccountId>) -> Result<(), ParticipantFilterError>;
+
+    /// Remove the accounts from the denylist. The accounts not denied are ignored
+    #[ink(message)]
+    fn remove_from_denylist(&mut self, accounts: Vec<AccountId>) -> Result<(), ParticipantFilterError>;
+
+    #[ink(message)]
+    fn is_denied(&self, account: AccountId) -> bool;
+
+    #[ink(message)]
+    fn get_nb_denied_accounts(&self) -> u32;
+
+    /// return at most limit denied accounts starting at the given offset (starting at 0)
+    #[ink(message)]
+    fn get_denied_accounts(&self, offset: u32, limit: u16) -> Vec<AccountId>;
+
+}
+
+#[openbrush::trait_definition]
+pub trait Internal {
+    fn _emit_added_to_denylist_event(&self, account: AccountId);
+    fn _emit_removed_from_denylist_event(&self, account: AccountId);
+}
@@ -0,0 +1,49 @@
+use openbrush::traits::{AccountId, Balance};
+use crate::traits::participant_filter::participant_filter::ParticipantFilterError;
+
+/// How the result of the external registry is read
+#[derive(Debug, Eq, PartialEq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum RegistryKind {
+    /// PSP34 balance_of(AccountId) -> u32: the holders of at least one token are eligible
+    Psp34BalanceOf,
+    /// is_eligible(AccountId) -> bool
+    IsEligible,
+}
+
+/// External contract called to check the eligibility of the participants
+#[derive(Debug, Eq, PartialEq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct EligibilityRegistry {
+    pub address: AccountId,
+    /// selector of the message called with the account of the participant
+    pub selector: [u8; 4],
+    pub kind: RegistryKind,
+}
+
+#[openbrush::trait_definition]
+pub trait FilterExternalRegistry {
+
+    /// Set the external registry used to check the eligibility of the participants of the pool.
+    /// The results already cached with the previous registry are ignored
+    #[ink(message)]
+    fn set_eligibility_registry(&mut self, pool_id: u32, address: AccountId, selector: [u8; 4], kind: RegistryKind) -> Result<(), ParticipantFilterError>;
+
+    #[ink(message)]
+    fn get_eligibility_registry(&self, pool_id: u32) -> Option<EligibilityRegistry>;
+
+    /// return the eligibility of the account cached for the pool and the era
+    #[ink(message)]
+    fn get_cached_eligibility(&self, pool_id: u32, era: u32, account: AccountId) -> Option<bool>;
+
+    /// Call the external registry for the participants without eligibility cached for the era
+    /// and save the results in the cache
+    fn _refresh_eligibility(&mut self, pool_id: u32, era: u32, participants: &[(AccountId, Balance)]) -> Result<(), ParticipantFilterError>;
+
+}
+
+#[openbrush::trait_definition]
+pub trait Internal {
+    /// call the external registry to check if the account is eligible
+    fn _call_eligibility_registry(&mut self, registry: &EligibilityRegistry, account: AccountId) -> Result<bool, ParticipantFilterError>;
+}
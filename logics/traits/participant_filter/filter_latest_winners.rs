@@ -5,18 +5,19 @@ use crate::traits::participant_filter::participant_filter::ParticipantFilterErro
 #[openbrush::trait_definition]
 pub trait FilterLatestWinners {
 
+    /// Set the number of last winners of the pool excluded from the next raffles of this pool
     #[ink(message)]
-    fn set_nb_winners_filtered(&mut self, nb_filtered_winners: u16) -> Result<(), ParticipantFilterError>;
+    fn set_nb_winners_filtered(&mut self, pool_id: u32, nb_filtered_winners: u16) -> Result<(), ParticipantFilterError>;
 
     #[ink(message)]
-    fn get_nb_winners_filtered(&self) -> u16;
+    fn get_nb_winners_filtered(&self, pool_id: u32) -> u16;
 
-    fn _add_winner(&mut self, winner: AccountId);
+    fn _add_winner(&mut self, pool_id: u32, winner: AccountId);
 
     #[ink(message)]
-    fn get_last_winners(&self) -> Vec<AccountId>;
+    fn get_last_winners(&self, pool_id: u32) -> Vec<AccountId>;
 
-    fn _is_in_last_winners(&self, participant: &AccountId) -> bool;
+    fn _is_in_last_winners(&self, pool_id: u32, participant: &AccountId) -> bool;
 
 }
 
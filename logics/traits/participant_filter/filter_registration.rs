@@ -0,0 +1,36 @@
+use ink::prelude::vec::Vec;
+use openbrush::traits::AccountId;
+use crate::traits::participant_filter::participant_filter::ParticipantFilterError;
+
+#[openbrush::trait_definition]
+pub trait FilterRegistration {
+
+    /// Register the caller: the caller opts in to receive the prizes of the raffles.
+    /// The registration only counts from the next era
+    #[ink(message)]
+    fn register(&mut self) -> Result<(), ParticipantFilterError>;
+
+    /// Unregister the caller: the caller will not be eligible anymore
+    #[ink(message)]
+    fn unregister(&mut self) -> Result<(), ParticipantFilterError>;
+
+    /// Register the accounts on their behalf. The accounts already registered are ignored
+    #[ink(message)]
+    fn register_accounts(&mut self, accounts: Vec<AccountId>) -> Result<(), ParticipantFilterError>;
+
+    #[ink(message)]
+    fn is_registered(&self, account: AccountId) -> bool;
+
+    /// return the era when the account has been registered
+    #[ink(message)]
+    fn get_registration_era(&self, account: AccountId) -> Option<u32>;
+
+}
+
+#[openbrush::trait_definition]
+pub trait Internal {
+    /// return the current era used to timestamp the registrations
+    fn _get_current_era(&self) -> u32;
+    fn _emit_registered_event(&self, account: AccountId, era: u32);
+    fn _emit_unregistered_event(&self, account: AccountId);
+}
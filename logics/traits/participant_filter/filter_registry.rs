@@ -0,0 +1,36 @@
+use ink::prelude::boxed::Box;
+use ink::prelude::vec::Vec;
+use openbrush::traits::{AccountId, Balance};
+use crate::traits::participant_filter::participant_filter::{FilterId, ParticipantFilter, ParticipantFilterError};
+
+#[openbrush::trait_definition]
+pub trait FilterRegistry {
+
+    /// Enable the filter for the pool, the filter is applied after the filters already enabled
+    #[ink(message)]
+    fn enable_filter(&mut self, pool_id: u32, filter: FilterId) -> Result<(), ParticipantFilterError>;
+
+    #[ink(message)]
+    fn disable_filter(&mut self, pool_id: u32, filter: FilterId) -> Result<(), ParticipantFilterError>;
+
+    /// Set the filters enabled for the pool in the order they are applied
+    #[ink(message)]
+    fn set_filters(&mut self, pool_id: u32, filters: Vec<FilterId>) -> Result<(), ParticipantFilterError>;
+
+    /// return the filters enabled for the pool in the order they are applied.
+    /// The filter LatestWinners is enabled by default, until the filters of the pool are changed
+    #[ink(message)]
+    fn get_filters(&self, pool_id: u32) -> Vec<FilterId>;
+
+    /// apply the chain of filters enabled for the pool on the participants.
+    /// The participants not eligible for a filter are removed and the weight returned by a filter is given to the next ones.
+    /// return the eligible participants with their weight and the excluded accounts with the filter which removed them
+    fn _apply_filters(&self, pool_id: u32, era: u32, participants: Vec<(AccountId, Balance)>) -> (Vec<(AccountId, Balance)>, Vec<(AccountId, FilterId)>);
+
+}
+
+#[openbrush::trait_definition]
+pub trait Internal {
+    /// return the implementation of the given filter
+    fn _get_participant_filter(&self, filter: FilterId) -> Box<dyn ParticipantFilter + '_>;
+}
@@ -0,0 +1,30 @@
+use openbrush::traits::AccountId;
+use crate::traits::participant_filter::participant_filter::ParticipantFilterError;
+
+#[openbrush::trait_definition]
+pub trait FilterWinnerCooldown {
+
+    /// Set the number of eras after winning a raffle of the pool when the winner is excluded from the next raffles of this pool
+    #[ink(message)]
+    fn set_nb_cooldown_eras(&mut self, pool_id: u32, nb_cooldown_eras: u32) -> Result<(), ParticipantFilterError>;
+
+    #[ink(message)]
+    fn get_nb_cooldown_eras(&self, pool_id: u32) -> u32;
+
+    /// Save the era when the account won a raffle of the pool
+    fn _set_last_win_era(&mut self, pool_id: u32, winner: AccountId, era: u32);
+
+    #[ink(message)]
+    fn get_last_win_era(&self, pool_id: u32, account: AccountId) -> Option<u32>;
+
+    /// return the number of eras before the account can participate again in the raffles of the pool
+    #[ink(message)]
+    fn get_cooldown_remaining(&self, pool_id: u32, account: AccountId) -> u32;
+
+}
+
+#[openbrush::trait_definition]
+pub trait Internal {
+    /// return the current era used to compute the remaining cooldown
+    fn _get_current_era(&self) -> u32;
+}
@@ -0,0 +1,20 @@
+use ink::prelude::vec::Vec;
+use openbrush::traits::{AccountId, Balance};
+use crate::traits::participant_filter::participant_filter::ParticipantFilterError;
+
+#[openbrush::trait_definition]
+pub trait FilterWinnerDecay {
+
+    /// Set the percentages (max 100) of the weight kept by the winners of the pool for the eras following their win:
+    /// the first percentage is applied on the next era, the second one on the era after, ...
+    /// For example [50, 75] halves the weight in the next era and keeps 75% of the weight in the era after
+    #[ink(message)]
+    fn set_decay_schedule(&mut self, pool_id: u32, decay_schedule: Vec<u16>) -> Result<(), ParticipantFilterError>;
+
+    #[ink(message)]
+    fn get_decay_schedule(&self, pool_id: u32) -> Vec<u16>;
+
+    /// Return the weight of the account reduced based on its last win in the pool, read from the cooldown filter
+    fn _get_decayed_weight(&self, pool_id: u32, era: u32, account: &AccountId, value: Balance) -> Balance;
+
+}
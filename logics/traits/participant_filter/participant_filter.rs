@@ -1,8 +1,62 @@
 use openbrush::contracts::access_control::AccessControlError;
+use openbrush::traits::{AccountId, Balance};
+
+/// Filters which can be enabled in the chain of filters applied on the participants
+#[derive(Debug, Eq, PartialEq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum FilterId {
+    /// the last winners of the pool are excluded
+    LatestWinners,
+    /// the accounts in the denylist are excluded
+    Denylist,
+    /// only the accounts registered before the era are eligible
+    Registration,
+    /// the winners of the pool are excluded during a number of eras
+    WinnerCooldown,
+    /// the weight of the winners of the pool is reduced during a number of eras
+    WinnerDecay,
+    /// the smart contracts are excluded, except the ones in the allowlist
+    ContractAccounts,
+    /// only the accounts checked as eligible by an external registry are eligible
+    ExternalRegistry,
+}
+
+/// Filter applied on the participants before they are added in the raffle.
+/// A filter can exclude the participant and/or modify its weight
+pub trait ParticipantFilter {
+
+    /// return true if the participant can be added in the raffle of the pool for the given era
+    fn is_eligible(&self, pool_id: u32, era: u32, account: &AccountId, value: Balance) -> bool;
+
+    /// return the weight of the eligible participant given to the next filters.
+    /// By default the weight is not modified
+    fn get_weight(&self, _pool_id: u32, _era: u32, _account: &AccountId, value: Balance) -> Balance {
+        value
+    }
+}
+
+/// the filters saved in the storage are given by reference in the chain of filters
+impl<F: ParticipantFilter + ?Sized> ParticipantFilter for &F {
+    fn is_eligible(&self, pool_id: u32, era: u32, account: &AccountId, value: Balance) -> bool {
+        (**self).is_eligible(pool_id, era, account, value)
+    }
+
+    fn get_weight(&self, pool_id: u32, era: u32, account: &AccountId, value: Balance) -> Balance {
+        (**self).get_weight(pool_id, era, account, value)
+    }
+}
 
 #[derive(Debug, Eq, PartialEq, scale::Encode, scale::Decode)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub enum ParticipantFilterError {
+    FilterAlreadyEnabled,
+    FilterNotEnabled,
+    DuplicateFilter,
+    AlreadyRegistered,
+    NotRegistered,
+    InvalidDecayPercentage,
+    EligibilityRegistryMissing,
+    EligibilityRegistryCallError,
     AccessControlError(AccessControlError),
 }
 
@@ -10,40 +10,254 @@ pub struct Participant {
     pub value: Balance,
 }
 
+/// Participant with its range of cumulative weights ]start, end]:
+/// the participant is selected when the drawn weight is in this range
+#[derive(Debug, Eq, PartialEq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct ParticipantRange {
+    pub account: AccountId,
+    pub value: Balance,
+    pub start: Balance,
+    pub end: Balance,
+}
+
+/// How an account already in the participants is managed when it is added again
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum DuplicateMode {
+    /// a new entry is added for each occurrence
+    #[default]
+    Allow,
+    /// the weight is added in the existing entry
+    Merge,
+    /// the participants are rejected with the error DuplicateParticipant
+    Reject,
+}
+
+/// Transformation applied on the weights of the participants when they are added
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum WeightPolicy {
+    /// the weight is the value
+    #[default]
+    Linear,
+    /// the weight is the square root of the value
+    SquareRoot,
+    /// the weight is the value limited to the given cap
+    Cap(Balance),
+    /// the weight is the number of bits of the value (floor(log2(value)) + 1)
+    Logarithmic,
+    /// the weight is 1 for all participants with a value
+    Equal,
+}
+
+/// How the participants with a value out of the thresholds are managed
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum ThresholdMode {
+    /// the participants are not added and counted as rejected
+    #[default]
+    Drop,
+    /// the participants are rejected with the error ValueOutOfRange
+    Reject,
+}
+
 #[openbrush::trait_definition]
 pub trait ParticipantManager {
 
+    /// Set the max number of participants by page and the max number of participants.
+    /// The page size must be at most MAX_PAGE_SIZE.
+    /// The max number of participants must be at least the page size and at most MAX_PARTICIPANTS_LIMIT.
+    /// Must be called when the contract is built
+    fn _init_participant_manager(&mut self, page_size: u32, max_participants: u32) -> Result<(), ParticipantManagerError>;
+
+    #[ink(message)]
+    fn get_page_size(&self) -> u32;
+
+    #[ink(message)]
+    fn get_max_participants(&self) -> u32;
+
+    /// open a new pool: each pool has its own participants by era
+    fn _create_pool(&mut self, pool_id: u32) -> Result<(), ParticipantManagerError>;
+
+    /// close the pool: no participant can be added anymore, the participants already stored can still be read or pruned
+    fn _close_pool(&mut self, pool_id: u32) -> Result<(), ParticipantManagerError>;
+
+    /// return the open pools
+    #[ink(message)]
+    fn get_pools(&self) -> Vec<u32>;
+
+    #[ink(message)]
+    fn is_pool_open(&self, pool_id: u32) -> bool;
+
+    /// return the last era for which participants have been added in the pool
+    #[ink(message)]
+    fn get_current_era(&self, pool_id: u32) -> u32;
+
+    /// return the eras with participants still stored in the pool
+    #[ink(message)]
+    fn get_eras(&self, pool_id: u32) -> Vec<u32>;
+
+    /// return the last era locked for a raffle in the pool.
+    /// The participants of this era and the previous ones can't be added, updated, removed or cleared
+    #[ink(message)]
+    fn get_locked_era(&self, pool_id: u32) -> Option<u32>;
+
+    /// lock the participants of the pool for the given era and the previous ones, used when a raffle is started
+    fn _lock_era(&mut self, pool_id: u32, era: u32);
+
+    /// return the number of participants of the pool for the current era
+    #[ink(message)]
+    fn get_nb_participants(&self, pool_id: u32) -> u32;
+
+    #[ink(message)]
+    fn get_nb_participants_for_era(&self, pool_id: u32, era: u32) -> u32;
+
+    /// return the number of distinct accounts in the participants of the pool for the given era
+    #[ink(message)]
+    fn get_nb_accounts_for_era(&self, pool_id: u32, era: u32) -> u32;
+
+    /// return the participants of the pool for the current era and the given page (starting at 1, the page 0 is not found)
     #[ink(message)]
-    fn get_nb_participants(&self) -> u16;
+    fn get_participants(&self, pool_id: u32, page: u32) -> Result<Vec<Participant>, ParticipantManagerError>;
 
+    /// return the participants of the pool for the given era and the given page (starting at 1, the page 0 is not found)
     #[ink(message)]
-    fn get_participants(&self, page: u8) -> Result<Vec<Participant>, ParticipantManagerError>;
+    fn get_participants_for_era(&self, pool_id: u32, era: u32, page: u32) -> Result<Vec<Participant>, ParticipantManagerError>;
 
+    /// return at most limit participants of the pool for the current era starting at the given offset (starting at 0)
+    /// and the total number of participants
     #[ink(message)]
-    fn get_total_value(&self) -> Balance;
+    fn get_participants_range(&self, pool_id: u32, offset: u32, limit: u16) -> (Vec<Participant>, u32);
 
+    /// return the entries of the given account in the pool for the current era with their ranges of cumulative weights,
+    /// sorted by position. The list is empty if the account is not a participant
     #[ink(message)]
-    fn get_participant(&self, weight: Balance) -> Option<AccountId>;
+    fn find_participant(&self, pool_id: u32, account: AccountId) -> Vec<ParticipantRange>;
 
-    /// add participants in the raffle
+    /// return the total weight of the pool for the current era
+    #[ink(message)]
+    fn get_total_value(&self, pool_id: u32) -> Balance;
+
+    #[ink(message)]
+    fn get_total_value_for_era(&self, pool_id: u32, era: u32) -> Balance;
+
+    /// return the participant of the pool for the current era matching with the weight
+    #[ink(message)]
+    fn get_participant(&self, pool_id: u32, weight: Balance) -> Option<AccountId>;
+
+    /// return the participant of the pool for the given era matching with the weight
+    #[ink(message)]
+    fn get_participant_for_era(&self, pool_id: u32, era: u32, weight: Balance) -> Option<AccountId>;
+
+    /// return the participant of the pool for the given era matching with the weight when the entries of the excluded accounts are removed
+    /// and the total weight of this participant (sum of all its entries)
+    fn _get_participant_excluding(&self, pool_id: u32, era: u32, weight: Balance, excluded: &[AccountId]) -> Option<(AccountId, Balance)>;
+
+    /// add participants in the raffle of the pool for the given era. The pool must be open
     /// a participant with a weight higher than another participant will have normally more chance to be selected in the raffle
     /// weight can represent the number of raffle tickets for this participant.
     /// weight can also represent the amount staked in dAppStaking, ...
+    /// the weight policy is applied on the sum of the values given for each account in the era,
+    /// the weight added for an account is the difference with the weight of its previous values
+    /// the participants with a value out of the thresholds are dropped or rejected depending on the threshold mode,
+    /// the thresholds are checked on the sum of the values given for each account in the era
+    /// return the number of participants accepted and the number of participants dropped
+    #[ink(message)]
+    fn add_participants(&mut self, pool_id: u32, era: u32, participants: Vec<(AccountId, Balance)>) -> Result<(u32, u32), ParticipantManagerError>;
+
+    /// return the participants with a value in the thresholds and the number of participants dropped.
+    /// The thresholds are checked on the sum of the values given for each account in the era,
+    /// the error ValueOutOfRange is returned if a participant is out of the thresholds in the Reject mode
+    fn _check_thresholds(&self, pool_id: u32, era: u32, participants: Vec<(AccountId, Balance)>) -> Result<(Vec<(AccountId, Balance)>, u32), ParticipantManagerError>;
+
+    /// add the participants already checked with the thresholds, nb_dropped is the number of participants dropped by the thresholds.
+    /// Used to check the thresholds on the values given before they are changed by the participant filters
+    /// return the number of participants accepted and the number of participants dropped
+    fn _add_participants(&mut self, pool_id: u32, era: u32, participants: Vec<(AccountId, Balance)>, nb_dropped: u32) -> Result<(u32, u32), ParticipantManagerError>;
+
+    /// return the weight of the given account (sum of all its entries) in the pool for the current era or None if the account is not a participant
+    #[ink(message)]
+    fn get_participant_value(&self, pool_id: u32, account: AccountId) -> Option<Balance>;
+
+    /// update the weight of the given participant in the pool for the current era, the weight policy is applied on the new value
+    /// which replaces the sum of the values given for this account
+    /// if the same account has been added many times, the first entry takes the new value and the other entries are removed
+    /// the pool must be open and the new value in the thresholds, otherwise the error ValueOutOfRange is returned whatever the threshold mode
+    #[ink(message)]
+    fn update_participant(&mut self, pool_id: u32, account: AccountId, new_value: Balance) -> Result<(), ParticipantManagerError>;
+
+    /// remove all entries of the given participant from the raffle of the pool for the current era
+    /// the last participant takes the position of each removed entry
+    #[ink(message)]
+    fn remove_participant(&mut self, pool_id: u32, account: AccountId) -> Result<(), ParticipantManagerError>;
+
+    /// Set how an account already in the participants is managed when it is added again
     #[ink(message)]
-    fn add_participants(&mut self, participants: Vec<(AccountId, Balance)>) -> Result<(), ParticipantManagerError>;
+    fn set_duplicate_mode(&mut self, duplicate_mode: DuplicateMode) -> Result<(), ParticipantManagerError>;
 
-    /// Clear the data (participants and rewards)
     #[ink(message)]
-    fn clear_data(&mut self) -> Result<(), ParticipantManagerError>;
+    fn get_duplicate_mode(&self) -> DuplicateMode;
+
+    /// Set the transformation applied on the values of the participants added or updated.
+    /// The weights of the participants already added are not changed
+    #[ink(message)]
+    fn set_weight_policy(&mut self, weight_policy: WeightPolicy) -> Result<(), ParticipantManagerError>;
+
+    #[ink(message)]
+    fn get_weight_policy(&self) -> WeightPolicy;
+
+    /// Set the min and max values (both included) of the participants added or updated. None means no limit
+    #[ink(message)]
+    fn set_value_thresholds(&mut self, min_value: Option<Balance>, max_value: Option<Balance>) -> Result<(), ParticipantManagerError>;
+
+    /// return the min and max values of the participants added
+    #[ink(message)]
+    fn get_value_thresholds(&self) -> (Option<Balance>, Option<Balance>);
+
+    /// Set how the participants with a value out of the thresholds are managed
+    #[ink(message)]
+    fn set_threshold_mode(&mut self, threshold_mode: ThresholdMode) -> Result<(), ParticipantManagerError>;
+
+    #[ink(message)]
+    fn get_threshold_mode(&self) -> ThresholdMode;
+
+    /// Clear the participants of the pool for the current era
+    #[ink(message)]
+    fn clear_data(&mut self, pool_id: u32) -> Result<(), ParticipantManagerError>;
+
+    /// Remove the participants of the pool for all eras before the given era to recover the storage deposit.
+    /// The given era can't be after the last locked era nor after the current era.
+    /// At most MAX_PRUNED_PAGES pages are removed by call, the call is repeated until the eras are no more returned by get_eras
+    #[ink(message)]
+    fn prune_eras(&mut self, pool_id: u32, before_era: u32) -> Result<(), ParticipantManagerError>;
 
 }
 
+#[openbrush::trait_definition]
+pub trait Internal {
+    fn _emit_participants_added_event(&self, pool_id: u32, era: u32, nb_accepted: u32, nb_rejected: u32, added_value: Balance, total_value: Balance);
+    fn _emit_participant_updated_event(&self, pool_id: u32, era: u32, account: AccountId, old_value: Balance, new_value: Balance);
+    fn _emit_participant_removed_event(&self, pool_id: u32, era: u32, account: AccountId, value: Balance);
+    fn _emit_data_cleared_event(&self, pool_id: u32, era: u32);
+}
 
 #[derive(Debug, Eq, PartialEq, scale::Encode, scale::Decode)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub enum ParticipantManagerError {
     MaxSizeExceeded,
     PageNotFound,
+    InvalidPageSize,
+    InvalidMaxParticipants,
+    ParticipantNotFound,
+    DuplicateParticipant,
+    ValueOutOfRange,
+    InvalidThresholds,
+    PoolNotFound,
+    PoolAlreadyExists,
+    EraLocked,
+    EraNotPrunable,
+    AddOverFlow,
     AccessControlError(AccessControlError),
 }
 
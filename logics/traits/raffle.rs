@@ -1,8 +1,35 @@
 use ink::prelude::vec::Vec;
 use openbrush::contracts::access_control::AccessControlError;
-use openbrush::traits::{AccountId, Balance};
+use openbrush::traits::{AccountId, Balance, Hash};
 use crate::traits::random::RandomError;
 
+/// Settings of the pool used to select the winners
+#[derive(Debug, Eq, PartialEq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct RaffleSettings {
+    pub ratio_distribution: Vec<Balance>,
+    pub total_ratio_distribution: Balance,
+    pub distinct_winners: bool,
+}
+
+/// Raffle started and waiting for the random seed to be finished
+#[derive(Debug, Eq, PartialEq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct PendingRaffle {
+    pub era: u32,
+    pub total_rewards: Balance,
+    /// id of the request sent to get the random seed
+    pub request_id: u32,
+    /// number of participants when the raffle is started
+    pub nb_participants: u32,
+    /// total weight of the participants when the raffle is started
+    pub total_value: Balance,
+    /// settings of the pool when the raffle is started, they can't be changed once the seed is known
+    pub settings: RaffleSettings,
+    /// timestamp of the block where the raffle is started
+    pub started_at: u64,
+}
+
 #[openbrush::trait_definition]
 pub trait Raffle {
 
@@ -11,38 +38,136 @@ pub trait Raffle {
     /// Second winner will receive : total_rewards * ratio[1] / total_ratio
     /// if ratio[n] equals to zero or is empty, tne winner n will receive nothing
     /// Sum(ratio[i]) <= total_ratio. Otherwise teh error IncorrectRatio is expected
+    /// Each pool has its own ratio distribution
+    #[ink(message)]
+    fn set_ratio_distribution(&mut self, pool_id: u32, ratio: Vec<Balance>, total_ratio: Balance) -> Result<(), RaffleError>;
+
+    #[ink(message)]
+    fn get_ratio_distribution(&self, pool_id: u32) -> Vec<Balance>;
+
     #[ink(message)]
-    fn set_ratio_distribution(&mut self, ratio: Vec<Balance>, total_ratio: Balance) -> Result<(), RaffleError>;
+    fn get_total_ratio_distribution(&self, pool_id: u32) -> Balance;
 
     #[ink(message)]
-    fn get_ratio_distribution(&self) -> Vec<Balance>;
+    fn get_last_era_done(&self, pool_id: u32) -> u32;
 
+    /// Set if an account can win only one prize by raffle in the given pool.
+    /// When true, the weight of a winner is removed from the next draws of the same raffle
     #[ink(message)]
-    fn get_total_ratio_distribution(&self) -> Balance;
+    fn set_distinct_winners(&mut self, pool_id: u32, distinct_winners: bool) -> Result<(), RaffleError>;
 
     #[ink(message)]
-    fn get_last_era_done(&self) -> u32;
+    fn is_distinct_winners(&self, pool_id: u32) -> bool;
 
+    /// return the raffle of the pool started and waiting for the random seed
+    #[ink(message)]
+    fn get_pending_raffle(&self, pool_id: u32) -> Option<PendingRaffle>;
+
+    /// return the current settings of the pool used to select the winners
+    fn _get_raffle_settings(&self, pool_id: u32) -> RaffleSettings;
+
+    /// check the pool is open, the raffle is not done or pending for the given era and the ratio and the rewards are set
+    fn _check_raffle_settings(&self, pool_id: u32, era: u32, total_rewards: Balance) -> Result<(), RaffleError>;
+
+    /// check the raffle can be run for the given era with the participants stored in the pool for this era
+    fn _check_raffle(&self, pool_id: u32, era: u32, total_rewards: Balance) -> Result<(), RaffleError>;
+
+    /// return the range (min, max) used to draw the random values for the given pool and era
+    fn _get_random_range(&self, pool_id: u32, era: u32, settings: &RaffleSettings) -> (u128, u128);
+
+    /// select the winners among the participants of the pool for the given era matching with the random values
+    /// and compute their rewards with the given settings
+    fn _select_winners(
+        &self,
+        pool_id: u32,
+        era: u32,
+        total_rewards: Balance,
+        settings: &RaffleSettings,
+        random_values: Vec<u128>
+    ) -> Result<Vec<(AccountId, Balance)>, RaffleError>;
+
+    /// draw the random values for the given era. If a secret is given, it is revealed with the draw
+    fn _draw_random_values(
+        &mut self,
+        era: u32,
+        secret: Option<[u8; 32]>,
+        count: u32,
+        min: u128,
+        max: u128
+    ) -> Result<Vec<u128>, RaffleError>;
+
+    /// run the raffle in one call for the participants of the pool for the given era: the random numbers are provided synchronously.
+    /// The secret committed for this era, if given, is revealed with the draw
     fn _run_raffle(
         &mut self,
+        pool_id: u32,
         era: u32,
-        total_rewards: Balance
+        total_rewards: Balance,
+        secret: Option<[u8; 32]>
     ) -> Result<Vec<(AccountId, Balance)>, RaffleError>;
 
+    /// run the raffle for the participants committed in a Merkle tree: only the total weight is known.
+    /// return the selected weight offsets and the rewards, the winners will be proven later.
+    /// An account can win many prizes, the option distinct_winners is not used
+    fn _draw_winning_offsets(
+        &mut self,
+        pool_id: u32,
+        era: u32,
+        total_value: Balance,
+        total_rewards: Balance,
+        secret: Option<[u8; 32]>
+    ) -> Result<Vec<(Balance, Balance)>, RaffleError>;
+
+    /// start the raffle of the pool for the given era and request the random seed.
+    /// The participants of this era are locked until the raffle is finished in a later call, when the seed is provided.
+    /// The secret committed for this era, if given, is revealed with the request.
+    /// return the id of the request
+    fn _start_raffle(
+        &mut self,
+        pool_id: u32,
+        era: u32,
+        total_rewards: Balance,
+        secret: Option<[u8; 32]>
+    ) -> Result<u32, RaffleError>;
+
+    /// finish the pending raffle of the pool with the random seed provided for the request.
+    /// The winners are selected with the settings saved when the raffle has been started.
+    /// return the pending raffle and the winners with their rewards
+    fn _finish_raffle(&mut self, pool_id: u32) -> Result<(PendingRaffle, Vec<(AccountId, Balance)>), RaffleError>;
+
+    /// commit the hash of the secret revealed with the draw of the given era.
+    /// The commitment must be done before any participant is added in this era:
+    /// the secret can't be chosen once the participants are known
+    fn _commit_secret(&mut self, era: u32, commitment: Hash) -> Result<(), RaffleError>;
+
+    /// cancel the pending raffle of the pool when the random seed has not been provided after PENDING_RAFFLE_TIMEOUT.
+    /// The participants of the era stay locked, the raffle can be started again for this era.
+    /// return the cancelled raffle
+    fn _cancel_raffle(&mut self, pool_id: u32) -> Result<PendingRaffle, RaffleError>;
+
 }
 
 #[derive(Debug, Eq, PartialEq, scale::Encode, scale::Decode)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub enum RaffleError {
+    PoolNotFound,
     RaffleAlreadyDone,
+    RaffleAlreadyPending,
+    NoPendingRaffle,
+    PendingRaffleNotExpired,
+    SeedAlreadyProvided,
     NoReward,
     NoRatioSet,
     IncorrectRatio,
     NoParticipant,
     NoSelectedParticipant,
+    NotEnoughParticipants,
+    ParticipantsAlreadyAdded,
+    ParticipantsChanged,
     DivByZero,
     MulOverFlow,
     AddOverFlow,
+    SubOverFlow,
     RandomError(RandomError),
     AccessControlError(AccessControlError),
 }
@@ -1,3 +1,5 @@
+use ink::prelude::vec::Vec;
+use openbrush::traits::Hash;
 use crate::traits::random_generator::RandomGeneratorError;
 
 pub trait Random {
@@ -5,12 +7,31 @@ pub trait Random {
     /// generate a random number between min and max values.
     fn get_random_number(&mut self, min: u128, max: u128) -> Result<u128, RandomError> ;
 
+    /// generate many random numbers between min and max values in one call.
+    fn get_random_numbers(&mut self, count: u32, min: u128, max: u128) -> Result<Vec<u128>, RandomError> ;
+
+    /// generate many random numbers between min and max values in one call
+    /// and reveal in the same call the secret committed for the given era.
+    fn get_random_numbers_with_secret(&mut self, era: u32, secret: [u8; 32], count: u32, min: u128, max: u128) -> Result<Vec<u128>, RandomError> ;
+
+    /// commit the hash of the secret revealed with the draw of the given era.
+    fn commit_secret(&mut self, era: u32, commitment: Hash) -> Result<(), RandomError> ;
+
+    /// request a random seed for the given era, the seed will be provided later by the oracle.
+    /// The secret committed for this era, if given, is revealed with the request and mixed in the seed.
+    /// return the id of the request
+    fn request_random_seed(&mut self, era: u32, secret: Option<[u8; 32]>) -> Result<u32, RandomError> ;
+
+    /// return the random seed provided by the oracle for the given request
+    fn get_random_seed(&mut self, request_id: u32) -> Result<[u8; 32], RandomError> ;
+
 }
 
 #[derive(Debug, Eq, PartialEq, scale::Encode, scale::Decode)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub enum RandomError {
     MissingAddress,
+    RandomnessNotFulfilled,
     RandomGeneratorError(RandomGeneratorError),
 }
 
@@ -1,4 +1,6 @@
+use ink::prelude::vec::Vec;
 use openbrush::contracts::access_control::AccessControlError;
+use openbrush::traits::Hash;
 
 #[openbrush::wrapper]
 pub type RandomGeneratorRef = dyn RandomGenerator;
@@ -11,13 +13,77 @@ pub trait RandomGenerator {
     #[ink(message)]
     fn get_random_number(&mut self, min: u128, max: u128) -> Result<u128, RandomGeneratorError> ;
 
+    /// generate many random numbers between min and max values.
+    /// All numbers are derived from a single seed so only one call is needed for many draws.
+    #[ink(message)]
+    fn get_random_numbers(&mut self, count: u32, min: u128, max: u128) -> Result<Vec<u128>, RandomGeneratorError> ;
+
+    /// generate many random numbers between min and max values and reveal the secret committed for the given era.
+    /// The secret must match the commitment, it is revealed in the same call as the draw and mixed in the seed
+    #[ink(message)]
+    fn get_random_numbers_with_secret(&mut self, era: u32, secret: [u8; 32], count: u32, min: u128, max: u128) -> Result<Vec<u128>, RandomGeneratorError> ;
+
     /// get the current salt used for randomness
     #[ink(message)]
     fn get_salt(&mut self) -> Result<u64, RandomGeneratorError> ;
 
-    /// Set the current salt used for randomness
+    /// Commit the hash (keccak256) of a secret for the given era.
+    /// The commitment must be done before the participants of this era are added, the raffle contract checks it before forwarding the commitment
+    #[ink(message)]
+    fn commit_secret(&mut self, era: u32, commitment: Hash) -> Result<(), RandomGeneratorError>;
+
+    /// check the secret matches with the commitment of the given era and store it
+    fn _reveal_secret(&mut self, era: u32, secret: [u8; 32]) -> Result<(), RandomGeneratorError>;
+
+    /// return the commitment for the given era
     #[ink(message)]
-    fn set_salt(&mut self, salt: u64) -> Result<(), RandomGeneratorError>;
+    fn get_commitment(&self, era: u32) -> Option<Hash>;
+
+    /// return true if the secret committed for the given era has been revealed
+    #[ink(message)]
+    fn is_secret_revealed(&self, era: u32) -> bool;
+
+    /// Request a random seed for the given era. The seed will be provided later by the oracle.
+    /// The secret committed for this era, if given, is revealed with the request and mixed in the seed provided by the oracle.
+    /// return the id of the request
+    #[ink(message)]
+    fn request_randomness(&mut self, era: u32, secret: Option<[u8; 32]>) -> Result<u32, RandomGeneratorError>;
+
+    /// Fulfill the request with the proof provided by the oracle.
+    /// The proof is the signature (ECDSA, 65 bytes) of the message returned by get_randomness_message
+    /// for the seed built on chain with the request (see get_request_seed), signed by the oracle key.
+    /// Only the canonical signature (low s value) is accepted. The verified signature becomes the source of entropy:
+    /// the oracle must sign with deterministic nonces (RFC 6979) so that only one proof exists for a request.
+    #[ink(message)]
+    fn fulfill_randomness(&mut self, request_id: u32, proof: Vec<u8>) -> Result<(), RandomGeneratorError>;
+
+    /// return the random seed provided for the given request or None if the request is not fulfilled yet
+    #[ink(message)]
+    fn get_randomness(&self, request_id: u32) -> Option<[u8; 32]>;
+
+    /// return the era of the given request
+    #[ink(message)]
+    fn get_request_era(&self, request_id: u32) -> Option<u32>;
+
+    /// return the seed built on chain for the given request, this seed must be signed by the oracle
+    #[ink(message)]
+    fn get_request_seed(&self, request_id: u32) -> Option<[u8; 32]>;
+
+    /// Register the public key (compressed ECDSA public key) of the oracle.
+    /// The previous key is replaced: the next fulfilments must be signed with the new key
+    #[ink(message)]
+    fn set_oracle_public_key(&mut self, public_key: [u8; 33]) -> Result<(), RandomGeneratorError>;
+
+    /// return the public key of the oracle
+    #[ink(message)]
+    fn get_oracle_public_key(&self) -> Option<[u8; 33]>;
+
+}
+
+#[openbrush::trait_definition]
+pub trait Internal {
+    fn _emit_oracle_public_key_set_event(&self, public_key: [u8; 33]);
+    fn _emit_randomness_verified_event(&self, request_id: u32, era: u32, randomness: [u8; 32]);
 
 }
 
@@ -30,6 +96,14 @@ pub enum RandomGeneratorError {
     AddOverFlow,
     SubOverFlow,
     MissingAddress,
+    CommitmentAlreadyExists,
+    MissingCommitment,
+    SecretAlreadyRevealed,
+    InvalidSecret,
+    RequestNotFound,
+    RequestAlreadyFulfilled,
+    MissingOraclePublicKey,
+    InvalidProof,
     AccessControlError(AccessControlError),
 }
 